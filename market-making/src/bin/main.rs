@@ -1,157 +1,276 @@
-use alloy::{network::EthereumWallet, primitives::Address, signers::local::PrivateKeySigner};
+use alloy::{primitives::Address, signers::local::PrivateKeySigner};
+use chrono::Weekday;
 use clap::Parser;
 use fastnum::{UD64, decimal::Context};
 use perpl_market_making_bot::{
     PerplMarketMakingBot,
-    strategies::{StrategyType, bbo::BboStrategy, spread::SpreadStrategy, taker::TakerStrategy},
+    config::{self, FeeConfig, MarketStrategyConfig, SignerConfig},
+    fee::{FeeEstimate, FixedFeeOracle},
+    signer::{BotSigner, HardwareSigner, HardwareWalletKind, LocalSigner, RemoteSigner},
+    strategies::{
+        StrategyType, bbo::BboStrategy, pegged_spread::PeggedSpreadStrategy,
+        quoting::InventoryModel, rollover::RolloverStrategy, spread::SpreadStrategy,
+        taker::TakerStrategy,
+    },
 };
 use perpl_sdk::Chain;
-use std::{process::exit, time::Duration};
+use std::{path::PathBuf, process::exit, sync::Arc, time::Duration};
 use tracing::error;
 use url::Url;
 
-#[derive(Debug, serde::Deserialize)]
-struct PerplConfig {
-    chain_id: u64,
-    collateral_token_address: String,
-    address: String,
-    private_key: String,
-    deployed_at_block: u64,
-    perpetual_id: u32,
-    node_rpc_url: String,
-    timeout_seconds: Option<u64>,
-}
-
 #[derive(Debug, clap::Parser)]
-enum StrategyConfig {
-    Bbo(BboStrategyArgs),
-    Spread(SpreadStrategyArgs),
-    Taker(TakerStrategyArgs),
-}
-
-#[derive(Debug, clap::Args)]
-struct BboStrategyArgs {
-    /// Size of each order
-    #[clap(long)]
-    order_size: String,
+struct Cli {
+    /// Path to the TOML bot configuration file
+    #[clap(long, env = "CONFIG_PATH", default_value = "config.toml")]
+    config: PathBuf,
 }
 
-#[derive(Debug, clap::Args)]
-struct SpreadStrategyArgs {
-    /// Number of orders to place on each side of the spread
-    #[clap(long)]
-    orders_per_side: usize,
-    /// The size of each order
-    #[clap(long)]
-    order_size: String,
-    /// Max matches per order
-    #[clap(long)]
-    max_matches: Option<u32>,
-    /// Leverage for each order
-    #[clap(long)]
-    leverage: Option<String>,
-}
-
-#[derive(Debug, clap::Args)]
-struct TakerStrategyArgs {
-    /// The size of each order
-    #[clap(long)]
-    order_size: String,
-    /// Leverage for each order
-    #[clap(long)]
-    leverage: Option<String>,
-}
-
-#[tokio::main]
-async fn main() {
-    dotenvy::dotenv().expect("Failed to load .env file");
+fn build_strategy(perpetual_id: u32, strategy: MarketStrategyConfig) -> StrategyType {
+    match strategy {
+        MarketStrategyConfig::Bbo {
+            order_size,
+            offset_bps,
+            skew_factor,
+            max_inventory,
+            max_open_orders,
+        } => StrategyType::Bbo(BboStrategy::new(
+            order_size.parse().expect("Invalid order_size"),
+            perpetual_id,
+            offset_bps.parse().expect("Invalid offset_bps"),
+            skew_factor.parse().expect("Invalid skew_factor"),
+            max_inventory.parse().expect("Invalid max_inventory"),
+            max_open_orders,
+        )),
+        MarketStrategyConfig::Spread {
+            orders_per_side,
+            order_size,
+            max_matches,
+            leverage,
+            offset_bps,
+            skew_factor,
+            max_inventory,
+            risk_aversion,
+            order_arrival_rate,
+            horizon,
+            volatility_ewma_alpha,
+            funding_skew_factor,
+            quote_ttl_blocks,
+            max_quote_distance_bps,
+        } => {
+            let leverage = leverage
+                .as_ref()
+                .map(|lev| UD64::from_str(lev, Context::default()).expect("Invalid leverage"));
 
-    let perpl_config =
-        envy::from_env::<PerplConfig>().expect("Failed to parse config from environment variables");
+            let inventory_model = match (risk_aversion, order_arrival_rate) {
+                (Some(risk_aversion), Some(order_arrival_rate)) => Some(InventoryModel::new(
+                    risk_aversion.parse().expect("Invalid risk_aversion"),
+                    order_arrival_rate.parse().expect("Invalid order_arrival_rate"),
+                    horizon.parse().expect("Invalid horizon"),
+                    volatility_ewma_alpha
+                        .parse()
+                        .expect("Invalid volatility_ewma_alpha"),
+                )),
+                _ => None,
+            };
 
-    let args = StrategyConfig::parse();
+            StrategyType::Spread(SpreadStrategy::new(
+                orders_per_side,
+                order_size.parse().expect("Invalid order_size"),
+                perpetual_id,
+                max_matches,
+                leverage.unwrap_or(UD64::ONE),
+                offset_bps.parse().expect("Invalid offset_bps"),
+                skew_factor.parse().expect("Invalid skew_factor"),
+                max_inventory.parse().expect("Invalid max_inventory"),
+                inventory_model,
+                funding_skew_factor.parse().expect("Invalid funding_skew_factor"),
+                quote_ttl_blocks,
+                max_quote_distance_bps.parse().expect("Invalid max_quote_distance_bps"),
+            ))
+        }
+        MarketStrategyConfig::Taker {
+            order_size,
+            leverage,
+        } => {
+            let leverage = leverage
+                .as_ref()
+                .map(|lev| UD64::from_str(lev, Context::default()).expect("Invalid leverage"));
 
-    let strategy = match args {
-        StrategyConfig::Bbo(args) => {
-            let order_size = args.order_size.parse().expect("Invalid order size");
-            StrategyType::Bbo(BboStrategy::new(order_size, perpl_config.perpetual_id))
+            StrategyType::Taker(TakerStrategy::new(
+                order_size.parse().expect("Invalid order_size"),
+                leverage.unwrap_or(UD64::ONE),
+                perpetual_id,
+            ))
         }
-        StrategyConfig::Spread(args) => {
-            let order_size = args.order_size.parse().expect("Invalid order size");
-            let leverage = args
-                .leverage
+        MarketStrategyConfig::PeggedSpread {
+            orders_per_side,
+            order_size,
+            offset_bps,
+            max_matches,
+            leverage,
+            peg_price_cap,
+            quote_ttl_blocks,
+            max_quote_distance_bps,
+        } => {
+            let leverage = leverage
                 .as_ref()
                 .map(|lev| UD64::from_str(lev, Context::default()).expect("Invalid leverage"));
+            let peg_price_cap = peg_price_cap
+                .as_ref()
+                .map(|cap| UD64::from_str(cap, Context::default()).expect("Invalid peg_price_cap"));
 
-            StrategyType::Spread(SpreadStrategy::new(
-                args.orders_per_side,
-                order_size,
-                perpl_config.perpetual_id,
-                args.max_matches,
+            StrategyType::PeggedSpread(PeggedSpreadStrategy::new(
+                orders_per_side,
+                order_size.parse().expect("Invalid order_size"),
+                perpetual_id,
+                offset_bps.parse().expect("Invalid offset_bps"),
+                max_matches,
                 leverage.unwrap_or(UD64::ONE),
+                peg_price_cap,
+                quote_ttl_blocks,
+                max_quote_distance_bps.parse().expect("Invalid max_quote_distance_bps"),
             ))
         }
-        StrategyConfig::Taker(args) => {
-            let order_size = args.order_size.parse().expect("Invalid order size");
-            let leverage = args
-                .leverage
+        MarketStrategyConfig::Rollover {
+            boundary_weekday,
+            boundary_hour,
+            lead_in_hours,
+            reopen_after_flatten,
+            leverage,
+        } => {
+            let boundary_weekday: Weekday =
+                boundary_weekday.parse().expect("Invalid boundary_weekday");
+            let leverage = leverage
                 .as_ref()
                 .map(|lev| UD64::from_str(lev, Context::default()).expect("Invalid leverage"));
 
-            StrategyType::Taker(TakerStrategy::new(
-                order_size,
+            StrategyType::Rollover(RolloverStrategy::new(
+                perpetual_id,
+                boundary_weekday,
+                boundary_hour,
+                chrono::Duration::hours(lead_in_hours),
+                reopen_after_flatten,
                 leverage.unwrap_or(UD64::ONE),
-                perpl_config.perpetual_id,
             ))
         }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    // Optional: secrets like PRIVATE_KEY are commonly kept out of the
+    // checked-in TOML config and supplied this way instead.
+    dotenvy::dotenv().ok();
+
+    let cli = Cli::parse();
+
+    let mut bot_config = match config::read_config(&cli.config) {
+        Ok(bot_config) => bot_config,
+        Err(error) => {
+            // Logging isn't set up yet, so print the error directly
+            eprintln!("Failed to load bot configuration: {error}");
+            exit(1);
+        }
     };
 
+    // Environment variables override file-based secrets
+    if let Ok(private_key) = std::env::var("PRIVATE_KEY") {
+        bot_config.private_key = Some(private_key);
+    }
+
     if std::env::var("RUST_LOG").is_err() {
         unsafe {
             std::env::set_var("RUST_LOG", "info");
         }
     }
 
-    let collateral_token_address: Address = perpl_config
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let collateral_token_address: Address = bot_config
         .collateral_token_address
         .parse()
         .expect("Invalid collateral token address");
-    let address: Address = perpl_config
-        .address
-        .parse()
-        .expect("Invalid exchange address");
-    let maker_private_key: PrivateKeySigner = perpl_config
-        .private_key
-        .parse()
-        .expect("Invalid maker private key");
+    let address: Address = bot_config.address.parse().expect("Invalid exchange address");
 
-    let wallet = EthereumWallet::new(maker_private_key);
+    let signer: Box<dyn BotSigner> = match bot_config.signer {
+        SignerConfig::Local => {
+            let maker_private_key: PrivateKeySigner = bot_config
+                .private_key
+                .expect("private_key is required when signer = \"local\"")
+                .parse()
+                .expect("Invalid maker private key");
 
-    let node_url = Url::parse(&perpl_config.node_rpc_url).expect("Invalid RPC URL");
+            Box::new(LocalSigner::new(maker_private_key))
+        }
+        SignerConfig::Ledger {
+            derivation_path,
+            address,
+        } => Box::new(HardwareSigner::new(
+            HardwareWalletKind::Ledger,
+            derivation_path,
+            address.parse().expect("Invalid Ledger account address"),
+        )),
+        SignerConfig::Trezor {
+            derivation_path,
+            address,
+        } => Box::new(HardwareSigner::new(
+            HardwareWalletKind::Trezor,
+            derivation_path,
+            address.parse().expect("Invalid Trezor account address"),
+        )),
+        SignerConfig::Remote { endpoint, address } => Box::new(RemoteSigner::new(
+            endpoint,
+            address.parse().expect("Invalid remote signer account address"),
+        )),
+    };
 
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .init();
+    let node_url = Url::parse(&bot_config.node_rpc_url).expect("Invalid RPC URL");
 
     // Default is 30 seconds if not specified
-    let timeout = Duration::from_secs(perpl_config.timeout_seconds.unwrap_or(30));
+    let timeout = Duration::from_secs(bot_config.timeout_seconds.unwrap_or(30));
+
+    let perpetual_ids = bot_config
+        .markets
+        .iter()
+        .map(|market| market.perpetual_id)
+        .collect();
+
+    let strategies = bot_config
+        .markets
+        .into_iter()
+        .map(|market| build_strategy(market.perpetual_id, market.strategy))
+        .collect();
 
     let mut bot = PerplMarketMakingBot::try_new(
         node_url,
-        wallet,
+        signer,
         Chain::custom(
-            perpl_config.chain_id,
+            bot_config.chain_id,
             collateral_token_address,
-            perpl_config.deployed_at_block,
+            bot_config.deployed_at_block,
             address,
-            vec![perpl_config.perpetual_id],
+            perpetual_ids,
         ),
         address,
-        strategy,
+        strategies,
         timeout,
     )
     .await
-    .expect("Failed to create market making bot");
+    .expect("Failed to create market making bot")
+    .with_confirmation_depth(bot_config.confirmation_depth.unwrap_or(0));
+
+    if let FeeConfig::Fixed {
+        max_fee_per_gas,
+        max_priority_fee_per_gas,
+    } = bot_config.fee
+    {
+        bot = bot.with_fee_oracle(Arc::new(FixedFeeOracle::new(FeeEstimate {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        })));
+    }
 
     if let Err(error) = bot.run().await {
         error!(%error, "Market making bot encountered an error, shutting down");
@@ -0,0 +1,124 @@
+use alloy::providers::DynProvider;
+use dex_sdk::{
+    abi::dex::Exchange::{ExchangeInstance, OrderDesc},
+    error::DexError,
+};
+use tokio::sync::{OwnedSemaphorePermit, mpsc};
+use tracing::{debug, error, warn};
+
+use crate::{
+    fee::{ConfirmationTarget, SharedFeeOracle},
+    nonce::SharedNonceScheduler,
+};
+
+/// Multiplier applied to the previous fee each time a submission is resent
+/// after being observed dropped or underpriced, as a percentage (125 = 1.25x).
+const FEE_BUMP_PERCENT: u128 = 125;
+
+/// Ceiling on how far a rebroadcast can bump the fee above the oracle's
+/// original estimate, as a multiple (4 = 4x), so repeated bumps can't spiral
+/// into an unbounded gas price.
+const MAX_FEE_MULTIPLE: u128 = 4;
+
+/// How many times to resend with a bumped fee before giving up and
+/// surfacing the error like any other submission failure.
+const MAX_REBROADCASTS: u32 = 3;
+
+/// Forward a submission error to the supervisor, logging rather than
+/// panicking if `error_rx` has already been dropped. This runs on a detached
+/// `tokio::spawn` outside the supervisor's `catch_unwind`, so a closed
+/// channel here is the normal shutdown/restart race (the supervisor dropped
+/// its receiver while this submission was still in flight), not a bug worth
+/// crashing the task over.
+async fn send_error(error_tx: &mpsc::Sender<DexError>, error: DexError, label: &'static str) {
+    if let Err(error) = error_tx.send(error).await {
+        warn!(%error, label, "Error channel closed before this submission's error could be forwarded, dropping it");
+    }
+}
+
+/// Reserve a nonce, attach a fee from `fee_oracle`, and submit
+/// `execOpsAndOrders(vec![], order_descs, cancel_all)`. If the transaction is
+/// dropped or stays underpriced (observed as an error while awaiting its
+/// receipt), or simply sits unconfirmed past `nonce_scheduler`'s
+/// `stuck_timeout`, resend the same nonce with a bumped fee up to
+/// `MAX_REBROADCASTS` times before giving up.
+///
+/// Runs entirely on the caller's task rather than being spawned itself, so
+/// callers that want to keep making progress while a submission (and its
+/// retries) are in flight should spawn this themselves, the way every
+/// strategy's submission path already does.
+pub async fn submit_with_fee_retry(
+    instance: &ExchangeInstance<DynProvider>,
+    fee_oracle: &SharedFeeOracle,
+    nonce_scheduler: &SharedNonceScheduler,
+    error_tx: &mpsc::Sender<DexError>,
+    permit: OwnedSemaphorePermit,
+    order_descs: Vec<OrderDesc>,
+    cancel_all: bool,
+    label: &'static str,
+) {
+    let nonce = nonce_scheduler.reserve_nonce().await;
+
+    let fee = match fee_oracle.estimate_fee(ConfirmationTarget::Normal).await {
+        Ok(fee) => fee,
+        Err(error) => {
+            error!(%error, label, "Failed to estimate a fee for this submission");
+            nonce_scheduler.mark_failed(nonce).await;
+            send_error(error_tx, DexError::from(error), label).await;
+            return;
+        }
+    };
+    let cap = fee.bumped(MAX_FEE_MULTIPLE * 100);
+    let mut attempt_fee = fee;
+
+    for attempt in 0..=MAX_REBROADCASTS {
+        let builder = instance
+            .execOpsAndOrders(vec![], order_descs.clone(), cancel_all)
+            .nonce(nonce)
+            .max_fee_per_gas(attempt_fee.max_fee_per_gas)
+            .max_priority_fee_per_gas(attempt_fee.max_priority_fee_per_gas);
+
+        let res = match builder.send().await.map_err(DexError::from) {
+            Ok(res) => res,
+            Err(error) => {
+                error!(%error, label, attempt, "Error sending transaction");
+                nonce_scheduler.mark_failed(nonce).await;
+                send_error(error_tx, error, label).await;
+                return;
+            }
+        };
+
+        nonce_scheduler.mark_submitted(nonce, *res.tx_hash()).await;
+
+        let res = res.with_timeout(Some(nonce_scheduler.stuck_timeout()));
+
+        match res.get_receipt().await.map_err(DexError::from) {
+            Ok(tx) => {
+                debug!(?tx, label, "Transaction confirmed");
+                nonce_scheduler.mark_complete(nonce).await;
+                drop(permit);
+                return;
+            }
+            Err(error) if attempt < MAX_REBROADCASTS => {
+                attempt_fee = attempt_fee.bumped(FEE_BUMP_PERCENT).capped(cap);
+                warn!(
+                    %error, label, attempt,
+                    next_max_fee_per_gas = attempt_fee.max_fee_per_gas,
+                    "Transaction dropped, underpriced, or stuck past timeout, resending with a bumped fee"
+                );
+
+                let provider = instance.provider().clone();
+                if let Err(resync_error) = nonce_scheduler.resync(&provider).await {
+                    error!(%resync_error, label, "Failed to resync nonce scheduler before rebroadcast");
+                }
+            }
+            Err(error) => {
+                error!(%error, label, "Transaction failed to confirm after fee-bumped rebroadcasts, giving up");
+                nonce_scheduler.mark_complete(nonce).await;
+                send_error(error_tx, error, label).await;
+                drop(permit);
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::{Result, error::Error};
+
+/// Top-level bot configuration, loaded from a TOML file. Secrets such as
+/// `private_key` are expected to be layered on top from the environment by
+/// the caller rather than checked into the file.
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    pub chain_id: u64,
+    pub collateral_token_address: String,
+    pub address: String,
+    /// Local private key, layered in from the `PRIVATE_KEY` environment
+    /// variable if set. Only read when `signer` is `local` (the default);
+    /// ignored for hardware and remote signers.
+    pub private_key: Option<String>,
+    pub deployed_at_block: u64,
+    pub node_rpc_url: String,
+    pub timeout_seconds: Option<u64>,
+    /// Blocks a block must sit below the tip before its events are
+    /// forwarded to strategies; unset leaves the bot forwarding events as
+    /// soon as they're applied, with no reorg protection
+    pub confirmation_depth: Option<u64>,
+    /// Where the bot's signing key material lives; defaults to a local key
+    /// read from `private_key`
+    #[serde(default)]
+    pub signer: SignerConfig,
+    /// Where the fee attached to order-submission transactions comes from;
+    /// defaults to the provider's own `eth_feeHistory`
+    #[serde(default)]
+    pub fee: FeeConfig,
+    /// One entry per perpetual the bot should trade, each bound to its own strategy
+    pub markets: Vec<MarketConfig>,
+}
+
+/// Mirrors `signer::BotSigner`'s implementations. The account address for
+/// hardware and remote signers is supplied explicitly since deriving it
+/// requires talking to the device or endpoint, which `read_config` doesn't
+/// do.
+#[derive(Debug, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignerConfig {
+    #[default]
+    Local,
+    Ledger {
+        derivation_path: Option<String>,
+        address: String,
+    },
+    Trezor {
+        derivation_path: Option<String>,
+        address: String,
+    },
+    Remote {
+        endpoint: String,
+        address: String,
+    },
+}
+
+/// Mirrors `fee::FeeOracle`'s implementations. `Fixed` is an escape hatch for
+/// an operator who wants to pin a fee (or has their own external fee market
+/// source feeding it in) rather than trusting the provider's own
+/// `eth_feeHistory`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeeConfig {
+    #[default]
+    Provider,
+    Fixed {
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MarketConfig {
+    pub perpetual_id: u32,
+    pub strategy: MarketStrategyConfig,
+}
+
+/// Per-market strategy settings, read from a `[[markets]]` table so several
+/// can be declared at once; `main.rs` builds a `StrategyType` from each.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MarketStrategyConfig {
+    Bbo {
+        order_size: String,
+        #[serde(default = "default_offset_bps")]
+        offset_bps: String,
+        #[serde(default = "default_skew_factor")]
+        skew_factor: String,
+        #[serde(default = "default_max_inventory")]
+        max_inventory: String,
+        /// Caps resting orders at one bid plus one ask; raise only if the
+        /// strategy grows to quote more than a single level per side
+        #[serde(default = "default_bbo_max_open_orders")]
+        max_open_orders: usize,
+    },
+    Spread {
+        orders_per_side: usize,
+        order_size: String,
+        max_matches: Option<u32>,
+        leverage: Option<String>,
+        #[serde(default = "default_spread_offset_bps")]
+        offset_bps: String,
+        #[serde(default = "default_skew_factor")]
+        skew_factor: String,
+        #[serde(default = "default_max_inventory")]
+        max_inventory: String,
+        /// Setting both `risk_aversion` and `order_arrival_rate` switches
+        /// this market to Avellaneda–Stoikov quoting instead of the
+        /// symmetric `offset_bps`/`skew_factor` quoting above
+        risk_aversion: Option<String>,
+        order_arrival_rate: Option<String>,
+        #[serde(default = "default_horizon")]
+        horizon: String,
+        #[serde(default = "default_volatility_ewma_alpha")]
+        volatility_ewma_alpha: String,
+        /// Scales how much the perpetual's funding rate skews quotes; zero
+        /// (the default) disables funding-aware skewing
+        #[serde(default = "default_funding_skew_factor")]
+        funding_skew_factor: String,
+        /// Blocks a resting order may go unchanged before it's cancelled as stale
+        #[serde(default = "default_quote_ttl_blocks")]
+        quote_ttl_blocks: u64,
+        /// Basis points of the index price a resting order may drift from the
+        /// mark before it's cancelled as stale
+        #[serde(default = "default_max_quote_distance_bps")]
+        max_quote_distance_bps: String,
+    },
+    Taker {
+        order_size: String,
+        leverage: Option<String>,
+    },
+    PeggedSpread {
+        orders_per_side: usize,
+        order_size: String,
+        #[serde(default = "default_spread_offset_bps")]
+        offset_bps: String,
+        max_matches: Option<u32>,
+        leverage: Option<String>,
+        /// Ceiling a pegged bid is never submitted above, guarding against a
+        /// fast upward oracle jump turning it into a taker
+        peg_price_cap: Option<String>,
+        /// Blocks a resting order may go unchanged before it's cancelled and
+        /// re-quoted, since there's no on-chain peg keeping it tied to the mark
+        #[serde(default = "default_quote_ttl_blocks")]
+        quote_ttl_blocks: u64,
+        /// Basis points of the index price a resting order may drift from the
+        /// mark before it's cancelled and re-quoted
+        #[serde(default = "default_max_quote_distance_bps")]
+        max_quote_distance_bps: String,
+    },
+    Rollover {
+        #[serde(default = "default_boundary_weekday")]
+        boundary_weekday: String,
+        #[serde(default)]
+        boundary_hour: u32,
+        #[serde(default = "default_lead_in_hours")]
+        lead_in_hours: i64,
+        #[serde(default)]
+        reopen_after_flatten: bool,
+        leverage: Option<String>,
+    },
+}
+
+fn default_offset_bps() -> String {
+    "10".to_string()
+}
+
+fn default_spread_offset_bps() -> String {
+    "20".to_string()
+}
+
+fn default_skew_factor() -> String {
+    "0".to_string()
+}
+
+fn default_max_inventory() -> String {
+    "1".to_string()
+}
+
+fn default_bbo_max_open_orders() -> usize {
+    2
+}
+
+fn default_boundary_weekday() -> String {
+    "Fri".to_string()
+}
+
+fn default_horizon() -> String {
+    "1".to_string()
+}
+
+fn default_volatility_ewma_alpha() -> String {
+    "0.1".to_string()
+}
+
+fn default_funding_skew_factor() -> String {
+    "0".to_string()
+}
+
+fn default_quote_ttl_blocks() -> u64 {
+    100
+}
+
+fn default_max_quote_distance_bps() -> String {
+    "200".to_string()
+}
+
+fn default_lead_in_hours() -> i64 {
+    1
+}
+
+/// Read and parse a TOML bot config from `path`, surfacing a contextual
+/// error rather than panicking if the file is missing or malformed.
+pub fn read_config(path: &Path) -> Result<Config> {
+    let contents = std::fs::read_to_string(path).map_err(|source| Error::ConfigRead {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    toml::from_str(&contents).map_err(|source| Error::ConfigParse {
+        path: path.to_path_buf(),
+        source,
+    })
+}
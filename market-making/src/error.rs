@@ -1,13 +1,29 @@
+use std::path::PathBuf;
+
 use perpl_sdk::{error::DexError, types::PerpetualId};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    #[error("Failed to read config file {path}: {source}")]
+    ConfigRead {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Failed to parse config file {path}: {source}")]
+    ConfigParse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
     #[error("Alloy contract error: {0}")]
     AlloyContract(#[from] alloy::contract::Error),
     #[error("Alloy local signer error: {0}")]
     AlloyLocalSigner(#[from] alloy::signers::local::LocalSignerError),
+    #[error("Alloy signer error: {0}")]
+    AlloySigner(#[from] alloy::signers::Error),
     #[error("Alloy pending transaction: {0}")]
     AlloyPendingTransaction(#[from] alloy::providers::PendingTransactionError),
+    #[error("Alloy transport error: {0}")]
+    AlloyTransport(#[from] alloy::transports::TransportError),
     #[error("Dex error: {0}")]
     Dex(#[from] DexError),
     #[error("Invalid RPC URL: {0}")]
@@ -22,4 +38,19 @@ pub enum Error {
     PerpetualNotFoundInExchangeState(PerpetualId),
     #[error("Strategy not initialized")]
     StrategyNotInitialized,
+    #[error("Fair-value quote computation overflowed")]
+    QuoteOverflow,
+    #[error(
+        "Chain reorg of depth {depth} exceeded the {confirmation_depth}-block confirmation \
+         depth, or reached state already forwarded to strategies; rebuilding from a fresh snapshot"
+    )]
+    Reorg { depth: u64, confirmation_depth: u64 },
+    /// Surfaced by `BotSigner::sign_transaction`/`sign_typed_data_hash` when
+    /// the configured signing device or endpoint can't be reached. Unlike
+    /// the startup-time check an earlier version of `BotSigner` did, this
+    /// now only surfaces the first time a signature is actually requested,
+    /// the same way a real hardware device or remote endpoint can only be
+    /// found unreachable when you actually ask it to sign something.
+    #[error("Signer unavailable: {0}")]
+    Signer(String),
 }
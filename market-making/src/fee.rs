@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use alloy::{eips::BlockNumberOrTag, providers::DynProvider};
+use futures::future::BoxFuture;
+
+use crate::Result;
+
+/// How urgently a transaction needs to land, trading off gas cost against
+/// inclusion speed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmationTarget {
+    Fast,
+    Normal,
+    Slow,
+}
+
+/// An EIP-1559 fee to attach to an order submission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimate {
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+}
+
+impl FeeEstimate {
+    /// Scale both components by `percent` (e.g. `125` = 1.25x), for resending
+    /// a transaction that's sat underpriced or was dropped from the mempool.
+    pub fn bumped(self, percent: u128) -> Self {
+        Self {
+            max_fee_per_gas: self.max_fee_per_gas * percent / 100,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas * percent / 100,
+        }
+    }
+
+    /// Clamp both components to at most `cap`'s, so a run of bumps can't
+    /// spiral into an unbounded fee.
+    pub fn capped(self, cap: FeeEstimate) -> Self {
+        Self {
+            max_fee_per_gas: self.max_fee_per_gas.min(cap.max_fee_per_gas),
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas.min(cap.max_priority_fee_per_gas),
+        }
+    }
+}
+
+/// Supplies the fee a strategy should attach to its next order transaction.
+/// Kept separate from actually sending and retrying
+/// (`broadcaster::submit_with_fee_retry`), mirroring the split LDK's
+/// chain-access module draws between `FeeEstimator` and
+/// `BroadcasterInterface`: this trait only answers "what should this cost",
+/// never "get it confirmed".
+///
+/// Returns a boxed future rather than an `async fn` so `with_fee_oracle` can
+/// take a `SharedFeeOracle` (an `Arc<dyn FeeOracle>`) instead of forcing
+/// every caller to know the concrete oracle type.
+pub trait FeeOracle: std::fmt::Debug + Send + Sync {
+    fn estimate_fee(&self, target: ConfirmationTarget) -> BoxFuture<'_, Result<FeeEstimate>>;
+}
+
+pub type SharedFeeOracle = Arc<dyn FeeOracle>;
+
+/// How many historical blocks' fee data to sample per `eth_feeHistory` call.
+const FEE_HISTORY_BLOCKS: u64 = 10;
+
+/// Priority fee to fall back to when a sampled block reports no rewards at
+/// the requested percentile (e.g. an empty block), in wei.
+const MIN_PRIORITY_FEE_PER_GAS: u128 = 1_000_000_000;
+
+/// Default `FeeOracle`, backed by the provider's own `eth_feeHistory`. Picks a
+/// base fee from the most recent sampled block and a priority fee from the
+/// reward percentile matching `target`.
+#[derive(Debug)]
+pub struct ProviderFeeOracle {
+    provider: DynProvider,
+}
+
+impl ProviderFeeOracle {
+    pub fn new(provider: DynProvider) -> Self {
+        Self { provider }
+    }
+
+    /// Reward percentile within each historical block to sample for `target`;
+    /// a more urgent target asks for a higher percentile of the tips other
+    /// transactions in that block paid.
+    fn reward_percentile(target: ConfirmationTarget) -> f64 {
+        match target {
+            ConfirmationTarget::Fast => 90.0,
+            ConfirmationTarget::Normal => 50.0,
+            ConfirmationTarget::Slow => 10.0,
+        }
+    }
+}
+
+impl FeeOracle for ProviderFeeOracle {
+    fn estimate_fee(&self, target: ConfirmationTarget) -> BoxFuture<'_, Result<FeeEstimate>> {
+        Box::pin(async move {
+            let percentile = Self::reward_percentile(target);
+            let history = self
+                .provider
+                .get_fee_history(FEE_HISTORY_BLOCKS, BlockNumberOrTag::Latest, &[percentile])
+                .await?;
+
+            let base_fee = *history
+                .base_fee_per_gas
+                .last()
+                .expect("eth_feeHistory always reports at least one base fee");
+
+            let priority_fee = history
+                .reward
+                .as_deref()
+                .and_then(|blocks| blocks.last())
+                .and_then(|rewards| rewards.first())
+                .copied()
+                .unwrap_or(MIN_PRIORITY_FEE_PER_GAS);
+
+            Ok(FeeEstimate {
+                max_fee_per_gas: base_fee * 2 + priority_fee,
+                max_priority_fee_per_gas: priority_fee,
+            })
+        })
+    }
+}
+
+/// A `FeeOracle` that always returns the same fee regardless of `target`, for
+/// wiring up to an external fee market API or an operator-supplied override
+/// instead of the provider's own `eth_feeHistory`.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedFeeOracle(FeeEstimate);
+
+impl FixedFeeOracle {
+    pub fn new(fee: FeeEstimate) -> Self {
+        Self(fee)
+    }
+}
+
+impl FeeOracle for FixedFeeOracle {
+    fn estimate_fee(&self, _target: ConfirmationTarget) -> BoxFuture<'_, Result<FeeEstimate>> {
+        Box::pin(async move { Ok(self.0) })
+    }
+}
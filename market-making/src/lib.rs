@@ -1,51 +1,99 @@
 use alloy::{
-    network::EthereumWallet,
     primitives::Address,
     providers::{DynProvider, ProviderBuilder},
     rpc::client::RpcClient,
 };
 use futures::StreamExt;
 use perpl_sdk::{
-    Chain, abi::dex::Exchange::ExchangeInstance, state::SnapshotBuilder, stream, types,
+    Chain,
+    abi::dex::Exchange::ExchangeInstance,
+    state::{SnapshotBuilder, StateEvents},
+    stream, types,
 };
+use perpl_utilities::event_source::EventSource;
 use std::{pin::pin, sync::Arc, time::Duration};
+use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 use url::Url;
 
-use crate::strategies::{Strategy, StrategyType};
+use crate::{
+    fee::{ProviderFeeOracle, SharedFeeOracle},
+    nonce::SharedNonceScheduler,
+    reorg::ReorgBuffer,
+    signer::BotSigner,
+    strategies::{Strategy, StrategyType},
+};
 
+pub mod broadcaster;
+pub mod config;
 pub mod error;
+pub mod fee;
+pub mod nonce;
+pub mod reorg;
+pub mod signer;
 pub mod strategies;
+pub mod supervisor;
 
 pub type Result<T> = std::result::Result<T, error::Error>;
 
+/// How long a submitted nonce's transaction can sit unconfirmed before
+/// `broadcaster::submit_with_fee_retry` treats it as stuck and resends it
+/// with a bumped fee.
+const NONCE_STUCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many blocks beyond `confirmation_depth` the reorg buffer keeps
+/// around, so a reorg can still be detected and rolled back shortly after
+/// its affected blocks cross the confirmation threshold.
+const REORG_BUFFER_MARGIN_BLOCKS: u64 = 64;
+
+/// How many order-submission transactions a single strategy may have
+/// in-flight at once. Now that `NonceScheduler` hands out sequential nonces
+/// itself, these no longer need to be serialized one-at-a-time to avoid
+/// nonce collisions; widening this beyond 1 just lets a strategy keep
+/// reacting to new blocks while an earlier submission is still pending.
+const MAX_CONCURRENT_SUBMISSIONS_PER_STRATEGY: usize = 4;
+
 #[derive(Debug)]
 pub struct PerplMarketMakingBot {
     provider: DynProvider,
     accounts: Vec<types::AccountAddressOrID>,
     instance: ExchangeInstance<DynProvider>,
     chain: Chain,
-    strategy: StrategyType,
+    strategies: Vec<StrategyType>,
     timeout: Duration,
+    nonce_scheduler: SharedNonceScheduler,
+    /// Fee to attach to order-submission transactions, set via
+    /// `with_fee_oracle`; defaults to `ProviderFeeOracle` in `try_new`
+    fee_oracle: SharedFeeOracle,
+    /// Kept around (rather than consumed into the `RpcClient` alone) so
+    /// `run` can resolve it through `EventSource::from_url`. A `ws://`/
+    /// `wss://` URL does not actually get a subscription transport today —
+    /// `perpl_sdk::stream` has none to select — see that type's docs for why
+    /// this bot can't deliver the push-based transport it was asked for
+    node_url: Url,
+    /// Number of blocks a block must sit below the tip before its events
+    /// are forwarded to strategies, set via `with_confirmation_depth`
+    confirmation_depth: u64,
 }
 
 impl PerplMarketMakingBot {
     pub async fn try_new(
         node_url: Url,
-        wallet: EthereumWallet,
+        signer: Box<dyn BotSigner>,
         chain: Chain,
         exchange_address: Address,
-        strategy: StrategyType,
+        strategies: Vec<StrategyType>,
         timeout: Duration,
     ) -> Result<Self> {
-        let wallet_address = wallet.default_signer().address();
+        let wallet_address = signer.address();
         info!(
-            strategy = strategy.name(),
+            strategies = ?strategies.iter().map(Strategy::name).collect::<Vec<_>>(),
             %wallet_address,
             %exchange_address,
             "Initializing Market Making Bot"
         );
-        let rpc_client = RpcClient::new_http(node_url);
+        let wallet = signer::into_wallet(signer);
+        let rpc_client = RpcClient::new_http(node_url.clone());
         let provider = DynProvider::new(
             ProviderBuilder::new()
                 .wallet(wallet)
@@ -53,39 +101,88 @@ impl PerplMarketMakingBot {
         );
 
         let instance = ExchangeInstance::new(exchange_address, provider.clone());
+        let nonce_scheduler =
+            nonce::build_scheduler(&provider, wallet_address, NONCE_STUCK_TIMEOUT).await?;
+        let fee_oracle: SharedFeeOracle = Arc::new(ProviderFeeOracle::new(provider.clone()));
 
         Ok(Self {
             provider,
             accounts: vec![types::AccountAddressOrID::Address(wallet_address)],
             instance,
             chain,
-            strategy,
+            strategies,
             timeout,
+            nonce_scheduler,
+            fee_oracle,
+            node_url,
+            confirmation_depth: 0,
         })
     }
 
+    /// Require a block to sit `confirmation_depth` blocks below the tip
+    /// before its events are forwarded to strategies, so a reorg shorter
+    /// than that can be rolled back before any strategy ever saw the state
+    /// it produced. Defaults to `0` (forward as soon as applied) if unset.
+    pub fn with_confirmation_depth(mut self, confirmation_depth: u64) -> Self {
+        self.confirmation_depth = confirmation_depth;
+        self
+    }
+
+    /// Replace the default `ProviderFeeOracle` (backed by the provider's own
+    /// `eth_feeHistory`) with a custom `FeeOracle`, e.g. one backed by an
+    /// external fee market API.
+    pub fn with_fee_oracle(mut self, fee_oracle: SharedFeeOracle) -> Self {
+        self.fee_oracle = fee_oracle;
+        self
+    }
+
+    /// Events are tagged with the perpetual they belong to; route each batch
+    /// only to the strategy trading that perpetual.
+    fn events_for(events: &[StateEvents], perpetual_id: types::PerpetualId) -> Vec<StateEvents> {
+        events
+            .iter()
+            .filter(|event| matches!(event, StateEvents::Order(order_event) if order_event.perpetual_id == perpetual_id))
+            .cloned()
+            .collect()
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         loop {
             info!("Starting new exchange snapshot and event stream");
             let snapshot_builder = SnapshotBuilder::new(&self.chain, self.provider.clone())
                 .with_accounts(self.accounts.clone())
-                .with_perpetuals(vec![self.strategy.perpetual_id()]);
+                .with_perpetuals(self.strategies.iter().map(Strategy::perpetual_id).collect());
 
             let mut exchange = snapshot_builder.build().await?;
             info!("Exchange snapshot built successfully");
 
-            let (error_tx, mut error_rx) = tokio::sync::mpsc::channel(100);
-
-            self.strategy
-                .initialize(&self.instance, &exchange)
-                .await
-                .inspect_err(|error| {
-                    error!(%error, "Strategy initialization failed");
-                })?;
+            for strategy in &mut self.strategies {
+                strategy
+                    .initialize(&self.instance, &exchange)
+                    .await
+                    .inspect_err(|error| {
+                        error!(strategy = strategy.name(), %error, "Strategy initialization failed");
+                    })?;
+            }
 
-            info!("Strategy initialized successfully, starting event processing loop");
+            info!("Strategies initialized successfully, starting supervised strategy tasks");
 
             let instance = exchange.instant();
+
+            // Resolves the event transport from the URL scheme; the
+            // irrefutable pattern is intentional so this call site breaks at
+            // compile time the day `EventSource` grows a second variant.
+            //
+            // There is only one variant today because `perpl_sdk::stream`
+            // has no subscription-driven transport to select: this bot still
+            // unconditionally drives `stream::raw`'s HTTP poll loop below
+            // regardless of `node_url`'s scheme. A `stream::subscribe`-style
+            // push transport would have to be added upstream in
+            // `perpl_sdk::stream`, an external crate this workspace depends
+            // on but doesn't vendor, so it can't be built from this repo.
+            // See `EventSource`'s docs for the full picture.
+            let EventSource::Poll = EventSource::from_url(&self.node_url);
+
             let mut dex_stream = pin!(stream::raw(
                 &self.chain,
                 self.provider.clone(),
@@ -93,81 +190,103 @@ impl PerplMarketMakingBot {
                 tokio::time::sleep,
             ));
 
-            let mut interval = tokio::time::interval(self.timeout);
-            interval.tick().await; // First tick completes immediately
+            let exchange = Arc::new(RwLock::new(exchange));
+            let mut reorg_buffer = ReorgBuffer::new(
+                self.confirmation_depth,
+                (self.confirmation_depth + REORG_BUFFER_MARGIN_BLOCKS) as usize,
+            );
 
-            let order_semaphore = Arc::new(tokio::sync::Semaphore::new(1));
-            let mut event_buffer = Vec::new();
+            // Each strategy runs on its own task, with its own event channel,
+            // so a crash in one strategy can't take down the others. Each
+            // channel buffers up to 100 batches; a strategy that falls
+            // further behind than that makes the event loop below wait on it.
+            let strategy_tasks = self
+                .strategies
+                .drain(..)
+                .map(|strategy| {
+                    let perpetual_id = strategy.perpetual_id();
+                    let (events_tx, events_rx) = tokio::sync::mpsc::channel(100);
+                    let handle = supervisor::spawn(
+                        strategy,
+                        self.instance.clone(),
+                        exchange.clone(),
+                        events_rx,
+                        self.nonce_scheduler.clone(),
+                        self.fee_oracle.clone(),
+                        self.timeout,
+                    );
+                    (perpetual_id, events_tx, handle)
+                })
+                .collect::<Vec<_>>();
 
             loop {
-                let order_semaphore = order_semaphore.clone();
-
-                tokio::select! {
-                    event = dex_stream.next() => {
-                        let Some(event) = event else {
-                            error!("DEX stream closed unexpectedly, restarting...");
-                            break;
-                        };
-
-                        let Ok(event) = event else {
-                            error!("Error in DEX event stream, will auto-restart");
-                            break;
-                        };
+                let Some(event) = dex_stream.next().await else {
+                    error!("DEX stream closed unexpectedly, restarting...");
+                    break;
+                };
 
-                        event_buffer.push(event);
+                let Ok(ev) = event else {
+                    error!("Error in DEX event stream, will auto-restart");
+                    break;
+                };
 
-                        let Ok(permit) = order_semaphore.try_acquire_owned() else {
-                            warn!("Previous strategy execution still in progress, skipping this event batch");
-                            continue;
-                        };
+                // Buffer the raw event by its chain linkage *before* it ever
+                // touches the shared exchange, so an orphaned block is
+                // dropped here instead of having already corrupted state
+                // that strategies read directly.
+                if let Err(error) = reorg_buffer.record(ev.block_hash(), ev.parent_hash(), ev) {
+                    error!(%error, "Unrecoverable chain reorg, restarting from a fresh snapshot");
+                    break;
+                }
 
-                        let mut block_events = Vec::new();
+                let confirmed_events = reorg_buffer.take_confirmed();
 
-                        for ev in event_buffer.drain(..) {
-                            let Some(result) = exchange.apply_events(&ev).unwrap() else {
-                                continue;
-                            };
+                if confirmed_events.is_empty() {
+                    continue;
+                }
 
-                            block_events.push(result);
-                        }
+                let mut state_events = Vec::new();
+                {
+                    let mut guard = exchange.write().await;
 
-                        if block_events.is_empty() {
+                    for ev in confirmed_events {
+                        let Some(result) = guard.apply_events(&ev).unwrap() else {
                             continue;
-                        }
-
-                        let state_events = block_events
-                            .into_iter()
-                            .flat_map(|b| b.events().iter().map(|ec| ec.event().clone()).collect::<Vec<_>>())
-                            .flatten()
-                            .collect::<Vec<_>>();
+                        };
 
-                        self.strategy
-                            .execute(&self.instance, &exchange, &state_events, &error_tx, permit)
-                            .await;
+                        state_events.extend(
+                            result
+                                .events()
+                                .iter()
+                                .map(|ec| ec.event().clone())
+                                .flatten(),
+                        );
                     }
-                    error = error_rx.recv() => {
-                        let Some(err) = error else {
-                            error!("Error channel closed unexpectedly, restarting...");
-                            break;
-                        };
+                }
 
-                        let Ok(permit) = order_semaphore.try_acquire_owned() else {
-                            warn!("Previous strategy execution still in progress, skipping this event batch");
-                            continue;
-                        };
+                if state_events.is_empty() {
+                    continue;
+                }
 
-                        warn!(%err, "Received error from strategy, will retry execution again if permitted");
-                        self.strategy.execute(&self.instance, &exchange, &[], &error_tx, permit).await;
-                    }
-                    _ = interval.tick() => {
-                        warn!("Timeout reached without receiving events, will run strategy execution just in case if permitted");
-                        let Ok(permit) = order_semaphore.try_acquire_owned() else {
-                            warn!("Previous strategy execution still in progress, skipping this event batch");
-                            continue;
-                        };
-                        self.strategy.execute(&self.instance, &exchange, &[], &error_tx, permit).await;
+                for (perpetual_id, events_tx, _handle) in &strategy_tasks {
+                    let events = Self::events_for(&state_events, *perpetual_id);
+                    if events_tx.send(events).await.is_err() {
+                        warn!(?perpetual_id, "Strategy task's event channel closed, it will not receive further events this session");
                     }
+                }
+            }
 
+            // Signal every strategy task to shut down by dropping its sender,
+            // then reclaim each strategy so it can be re-initialized against
+            // the fresh snapshot built at the top of the next iteration.
+            for (perpetual_id, events_tx, handle) in strategy_tasks {
+                drop(events_tx);
+
+                match handle.await {
+                    Ok(strategy) => self.strategies.push(strategy),
+                    Err(error) => {
+                        error!(?perpetual_id, %error, "Strategy task ended unexpectedly while shutting down for restart");
+                    }
                 }
             }
         }
@@ -0,0 +1,152 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use alloy::{
+    primitives::{Address, TxHash},
+    providers::Provider,
+};
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use crate::Result;
+
+/// Hands out sequential nonces for a single account so that concurrent
+/// `execute` calls cannot race to the mempool and collide on the same nonce.
+///
+/// A strategy reserves a nonce immediately before building its
+/// `execOpsAndOrders` call, marks it submitted once the transaction is sent,
+/// and marks it complete once the receipt lands. The actual stuck-timeout
+/// rebroadcast lives in `broadcaster::submit_with_fee_retry`, which attaches
+/// `stuck_timeout` to the pending transaction watch and treats a timeout the
+/// same as a dropped or underpriced send; `stuck_timeout` is exposed here so
+/// that one call site stays the source of truth for the duration.
+#[derive(Debug)]
+pub struct NonceScheduler {
+    address: Address,
+    state: Mutex<SchedulerState>,
+    stuck_timeout: tokio::time::Duration,
+}
+
+#[derive(Debug)]
+struct SchedulerState {
+    next_nonce: u64,
+    pending: BTreeMap<u64, TxHash>,
+    /// Nonces reserved but never broadcast (the `send` call itself failed),
+    /// left as gaps until the next `resync` picks up the chain's real
+    /// transaction count.
+    skipped: BTreeSet<u64>,
+}
+
+impl NonceScheduler {
+    /// Create a scheduler starting from `starting_nonce`, the account's current
+    /// on-chain transaction count.
+    pub fn new(address: Address, starting_nonce: u64, stuck_timeout: tokio::time::Duration) -> Self {
+        Self {
+            address,
+            state: Mutex::new(SchedulerState {
+                next_nonce: starting_nonce,
+                pending: BTreeMap::new(),
+                skipped: BTreeSet::new(),
+            }),
+            stuck_timeout,
+        }
+    }
+
+    /// Reserve the next sequential nonce for a submission.
+    pub async fn reserve_nonce(&self) -> u64 {
+        let mut state = self.state.lock().await;
+        let nonce = state.next_nonce;
+        state.next_nonce += 1;
+        nonce
+    }
+
+    /// Record that `nonce` was submitted as `tx_hash`, so the scheduler keeps
+    /// it pending until `mark_complete` or `mark_failed`.
+    pub async fn mark_submitted(&self, nonce: u64, tx_hash: TxHash) {
+        let mut state = self.state.lock().await;
+        state.pending.insert(nonce, tx_hash);
+    }
+
+    /// Record that `nonce` has been mined (or permanently failed), freeing it
+    /// from the pending set.
+    pub async fn mark_complete(&self, nonce: u64) {
+        let mut state = self.state.lock().await;
+        state.pending.remove(&nonce);
+        state.skipped.remove(&nonce);
+    }
+
+    /// Record that a reserved nonce's transaction never made it to the
+    /// mempool (the `send` call itself failed, before a `tx_hash` existed to
+    /// track). Rolls the local counter back if no later nonce has since been
+    /// reserved and no other nonce is still pending, otherwise leaves a gap
+    /// for the next `resync` to close.
+    pub async fn mark_failed(&self, nonce: u64) {
+        let mut state = self.state.lock().await;
+        state.pending.remove(&nonce);
+
+        if state.next_nonce == nonce + 1 && state.pending.is_empty() {
+            state.next_nonce = nonce;
+        } else {
+            warn!(nonce, "Reserved nonce never broadcast, leaving a gap until the next resync");
+            state.skipped.insert(nonce);
+        }
+    }
+
+    /// How long a submitted nonce's transaction can sit unconfirmed before
+    /// `broadcaster::submit_with_fee_retry` treats it as stuck and resends it
+    /// with a bumped fee.
+    pub fn stuck_timeout(&self) -> tokio::time::Duration {
+        self.stuck_timeout
+    }
+
+    /// Re-fetch the account's on-chain transaction count and reconcile the
+    /// scheduler against it, so a nonce gap left by `mark_failed` (or a
+    /// dropped/replaced transaction surfaced as `Error::AlloyPendingTransaction`
+    /// further up the stack) doesn't stall every later reservation forever.
+    pub async fn resync(&self, provider: &impl Provider) -> Result<()> {
+        let chain_nonce = fetch_starting_nonce(provider, self.address).await?;
+        let mut state = self.state.lock().await;
+
+        state.pending.retain(|&nonce, _| nonce >= chain_nonce);
+        state.skipped.retain(|&nonce| nonce >= chain_nonce);
+        state.next_nonce = state.next_nonce.max(chain_nonce);
+
+        info!(
+            chain_nonce,
+            next_nonce = state.next_nonce,
+            "Resynced nonce scheduler from the chain's transaction count"
+        );
+
+        Ok(())
+    }
+}
+
+/// Resolve the nonce a provider believes is next for `address`, to seed a new
+/// `NonceScheduler` on startup.
+pub async fn fetch_starting_nonce(
+    provider: &impl Provider,
+    address: alloy::primitives::Address,
+) -> Result<u64> {
+    let nonce = provider.get_transaction_count(address).await?;
+    debug!(%address, nonce, "Fetched starting nonce for account");
+    Ok(nonce)
+}
+
+pub type SharedNonceScheduler = Arc<NonceScheduler>;
+
+/// Build a shared scheduler for `address`, to be cloned into each strategy task
+/// that submits transactions on that account's behalf.
+pub async fn build_scheduler(
+    provider: &impl Provider,
+    address: alloy::primitives::Address,
+    stuck_timeout: tokio::time::Duration,
+) -> Result<SharedNonceScheduler> {
+    let starting_nonce = fetch_starting_nonce(provider, address).await?;
+    Ok(Arc::new(NonceScheduler::new(
+        address,
+        starting_nonce,
+        stuck_timeout,
+    )))
+}
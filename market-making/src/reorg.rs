@@ -0,0 +1,157 @@
+use std::collections::VecDeque;
+
+use alloy::primitives::B256;
+use tracing::warn;
+
+use crate::{Result, error::Error};
+
+/// One buffered block's canonical-chain linkage plus its not-yet-applied raw
+/// event, kept only long enough to detect and roll back a reorg before the
+/// event is ever applied to the shared exchange state. `raw_event` is taken
+/// (leaving `None` behind) once `take_confirmed` hands it to the caller, but
+/// the linkage metadata is kept around until evicted by `capacity` so a
+/// later, deeper reorg can still be recognized as reaching already-forwarded
+/// state.
+#[derive(Debug)]
+struct BufferedBlock<E> {
+    /// Monotonic position in the sequence of blocks ever recorded, used to
+    /// tell "already forwarded to strategies" apart from "still pending
+    /// confirmation" independent of where the block currently sits in the
+    /// ring buffer.
+    index: u64,
+    block_hash: B256,
+    parent_hash: B256,
+    raw_event: Option<E>,
+}
+
+/// Bounded ring buffer of recently seen, not-yet-applied block events.
+/// Detects a chain reorg by checking each incoming block's `parent_hash`
+/// against the buffered tip and walking back to the common ancestor,
+/// dropping any orphaned block's raw event before it is ever applied to the
+/// shared exchange state, and only hands a block's raw event to the caller
+/// via `take_confirmed` once it is `confirmation_depth` blocks below the
+/// tip — so a strategy reading the shared exchange directly can never
+/// observe state that a reorg could still unwind out from under it.
+#[derive(Debug)]
+pub struct ReorgBuffer<E> {
+    blocks: VecDeque<BufferedBlock<E>>,
+    capacity: usize,
+    confirmation_depth: u64,
+    next_index: u64,
+    last_forwarded_index: Option<u64>,
+}
+
+impl<E> ReorgBuffer<E> {
+    /// `capacity` bounds how many blocks back a reorg can be detected and
+    /// rolled back from; a reorg whose common ancestor falls outside the
+    /// buffer, or behind state already forwarded to strategies, surfaces as
+    /// `Error::Reorg` instead of being silently handled.
+    pub fn new(confirmation_depth: u64, capacity: usize) -> Self {
+        Self {
+            blocks: VecDeque::with_capacity(capacity),
+            capacity,
+            confirmation_depth,
+            next_index: 0,
+            last_forwarded_index: None,
+        }
+    }
+
+    /// Record a newly seen block's canonical-chain linkage and its
+    /// not-yet-applied raw event. If it doesn't extend the buffered tip,
+    /// walk back to the common ancestor and drop the orphaned blocks'
+    /// events unapplied, erroring instead if the fork point falls outside
+    /// the buffer or behind what's already been confirmed and forwarded.
+    pub fn record(&mut self, block_hash: B256, parent_hash: B256, raw_event: E) -> Result<()> {
+        if let Some(tip) = self.blocks.back()
+            && tip.block_hash != parent_hash
+        {
+            let mut depth = 0u64;
+
+            while let Some(candidate) = self.blocks.back() {
+                if candidate.block_hash == parent_hash {
+                    break;
+                }
+
+                depth += 1;
+                let orphan = self.blocks.pop_back().expect("checked non-empty above");
+
+                warn!(
+                    block_hash = %orphan.block_hash,
+                    applied = orphan.raw_event.is_none(),
+                    "Orphaning buffered block due to chain reorg"
+                );
+
+                if self
+                    .last_forwarded_index
+                    .is_some_and(|forwarded| orphan.index <= forwarded)
+                {
+                    return Err(Error::Reorg {
+                        depth,
+                        confirmation_depth: self.confirmation_depth,
+                    });
+                }
+            }
+
+            if self.blocks.is_empty() {
+                // Walked back through the whole buffer without finding a
+                // matching ancestor hash: the fork predates everything we
+                // retained.
+                return Err(Error::Reorg {
+                    depth,
+                    confirmation_depth: self.confirmation_depth,
+                });
+            }
+        }
+
+        self.blocks.push_back(BufferedBlock {
+            index: self.next_index,
+            block_hash,
+            parent_hash,
+            raw_event: Some(raw_event),
+        });
+        self.next_index += 1;
+
+        if self.blocks.len() > self.capacity {
+            self.blocks.pop_front();
+        }
+
+        Ok(())
+    }
+
+    /// Raw events of any buffered blocks that have just crossed
+    /// `confirmation_depth` below the tip, safe to apply to the shared
+    /// exchange and hand to strategies. Each block's event is returned
+    /// exactly once, oldest first; by the time a block's event is returned
+    /// here, a reorg can no longer orphan it out from under the caller.
+    pub fn take_confirmed(&mut self) -> Vec<E> {
+        // The buffer always holds a contiguous chain ending at the tip, so a
+        // block's depth below the tip is its distance from the back. The
+        // record `index` can't be used for this: it keeps counting orphaned
+        // blocks, so after a reorg it runs ahead of the chain's height.
+        let Some(confirmed_len) = self
+            .blocks
+            .len()
+            .checked_sub(self.confirmation_depth as usize)
+        else {
+            return Vec::new();
+        };
+
+        let mut confirmed = Vec::new();
+
+        for block in self.blocks.iter_mut().take(confirmed_len) {
+            if self
+                .last_forwarded_index
+                .is_some_and(|forwarded| block.index <= forwarded)
+            {
+                continue;
+            }
+
+            if let Some(raw_event) = block.raw_event.take() {
+                confirmed.push(raw_event);
+            }
+            self.last_forwarded_index = Some(block.index);
+        }
+
+        confirmed
+    }
+}
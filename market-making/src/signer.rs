@@ -0,0 +1,199 @@
+use alloy::{
+    consensus::SignableTransaction,
+    network::{EthereumWallet, TxSigner},
+    primitives::{Address, B256, Signature},
+    signers::{Signer as AlloySigner, local::PrivateKeySigner},
+};
+use async_trait::async_trait;
+
+use crate::{Result, error::Error};
+
+/// Abstracts over where the bot's signing key material actually lives, so
+/// `try_new` doesn't have to hard-code a local private key.
+///
+/// An earlier version of this trait funnelled every implementation through
+/// `into_wallet(self) -> Result<EthereumWallet>`, which structurally
+/// requires materializing a local private key to build the wallet from —
+/// permanently dead weight for `HardwareSigner`/`RemoteSigner`, which never
+/// have one. `sign_transaction`/`sign_typed_data_hash` are exposed directly
+/// instead, so a signer can hand back a signature produced however it likes
+/// (a hardware device's secure element, a remote KMS call) without a local
+/// key ever existing. `into_wallet` (below, free-standing) adapts any
+/// `BotSigner` into the `EthereumWallet` the provider is actually built
+/// from.
+///
+/// Dyn-dispatched (`try_new` takes `Box<dyn BotSigner>`), so this uses
+/// `async_trait` rather than the native `-> impl Future` style `Strategy`
+/// uses, since `Strategy` is only ever used through the monomorphized
+/// `StrategyType` enum and never boxed as a trait object.
+#[async_trait]
+pub trait BotSigner: std::fmt::Debug + Send + Sync {
+    /// The account address this signer signs on behalf of.
+    fn address(&self) -> Address;
+
+    /// Sign a transaction in place. Mirrors `alloy::network::TxSigner`'s
+    /// method exactly, since `into_wallet` adapts this trait to that one to
+    /// build the provider's wallet.
+    async fn sign_transaction(&self, tx: &mut dyn SignableTransaction<Signature>) -> Result<Signature>;
+
+    /// Sign an EIP-712 typed-data payload that the caller has already
+    /// hashed via `SolStruct::eip712_signing_hash`. Takes the hash rather
+    /// than a generic `SolStruct` (as `alloy::signers::Signer::sign_typed_data`
+    /// does) because a generic method isn't object-safe, and every caller of
+    /// this trait goes through `Box<dyn BotSigner>`.
+    async fn sign_typed_data_hash(&self, hash: B256) -> Result<Signature>;
+}
+
+/// Adapts any `BotSigner` into `alloy::network::TxSigner`, the trait
+/// `EthereumWallet` is actually built from, so `try_new` can wire the
+/// provider to a hardware/remote signer without ever asking it to produce a
+/// wallet of its own.
+#[derive(Debug)]
+struct WalletAdapter(Box<dyn BotSigner>);
+
+#[async_trait]
+impl TxSigner<Signature> for WalletAdapter {
+    fn address(&self) -> Address {
+        self.0.address()
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<Signature>,
+    ) -> alloy::signers::Result<Signature> {
+        self.0
+            .sign_transaction(tx)
+            .await
+            .map_err(alloy::signers::Error::other)
+    }
+}
+
+/// Build the `EthereumWallet` `try_new` wires into the provider from any
+/// `BotSigner`, whether or not it's backed by an in-process key.
+pub fn into_wallet(signer: Box<dyn BotSigner>) -> EthereumWallet {
+    EthereumWallet::new(WalletAdapter(signer))
+}
+
+/// Signs with a private key held in process memory, exactly as `try_new`
+/// did before signer selection became pluggable.
+#[derive(Debug)]
+pub struct LocalSigner(PrivateKeySigner);
+
+impl LocalSigner {
+    pub fn new(signer: PrivateKeySigner) -> Self {
+        Self(signer)
+    }
+}
+
+#[async_trait]
+impl BotSigner for LocalSigner {
+    fn address(&self) -> Address {
+        self.0.address()
+    }
+
+    async fn sign_transaction(&self, tx: &mut dyn SignableTransaction<Signature>) -> Result<Signature> {
+        TxSigner::sign_transaction(&self.0, tx)
+            .await
+            .map_err(Error::from)
+    }
+
+    async fn sign_typed_data_hash(&self, hash: B256) -> Result<Signature> {
+        self.0.sign_hash(&hash).await.map_err(Error::from)
+    }
+}
+
+/// Which hardware wallet protocol to speak to the connected device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HardwareWalletKind {
+    Ledger,
+    Trezor,
+}
+
+/// Signs via a connected Ledger or Trezor device, so the key material never
+/// leaves the hardware wallet. Wiring the actual device transport (e.g.
+/// `alloy-signer-ledger`/`alloy-signer-trezor`) is left for an operator who
+/// actually has the hardware to test against; until then, every sign call
+/// surfaces a clear `Error::Signer` rather than silently falling back to an
+/// unrelated signer. Unlike the `into_wallet`-based design this replaced,
+/// that's no longer a structural dead end: once a real device transport is
+/// plugged into `sign_transaction`/`sign_typed_data_hash` below, this signer
+/// works without ever needing to hand back a local private key.
+#[derive(Debug)]
+pub struct HardwareSigner {
+    kind: HardwareWalletKind,
+    derivation_path: Option<String>,
+    address: Address,
+}
+
+impl HardwareSigner {
+    pub fn new(kind: HardwareWalletKind, derivation_path: Option<String>, address: Address) -> Self {
+        Self {
+            kind,
+            derivation_path,
+            address,
+        }
+    }
+
+    fn unavailable(&self) -> Error {
+        Error::Signer(format!(
+            "{:?} hardware signing is not wired up in this example bot yet (derivation path {:?}); \
+             connect a real device transport (e.g. alloy-signer-ledger/alloy-signer-trezor) first",
+            self.kind, self.derivation_path
+        ))
+    }
+}
+
+#[async_trait]
+impl BotSigner for HardwareSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_transaction(&self, _tx: &mut dyn SignableTransaction<Signature>) -> Result<Signature> {
+        Err(self.unavailable())
+    }
+
+    async fn sign_typed_data_hash(&self, _hash: B256) -> Result<Signature> {
+        Err(self.unavailable())
+    }
+}
+
+/// Signs by delegating to a remote signer endpoint (an HTTP/gRPC KMS), so no
+/// key material ever touches this host. The endpoint itself is
+/// deployment-specific, so actually calling out to it is left unimplemented
+/// here; every sign call surfaces `Error::Signer` instead of a hung or
+/// panicking provider until that client is wired up.
+#[derive(Debug)]
+pub struct RemoteSigner {
+    endpoint: String,
+    address: Address,
+}
+
+impl RemoteSigner {
+    pub fn new(endpoint: String, address: Address) -> Self {
+        Self { endpoint, address }
+    }
+
+    fn unavailable(&self) -> Error {
+        Error::Signer(format!(
+            "remote signer endpoint {} is not wired up in this example bot yet; \
+             implement the KMS client's signing RPC here",
+            self.endpoint
+        ))
+    }
+}
+
+#[async_trait]
+impl BotSigner for RemoteSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_transaction(&self, _tx: &mut dyn SignableTransaction<Signature>) -> Result<Signature> {
+        Err(self.unavailable())
+    }
+
+    async fn sign_typed_data_hash(&self, _hash: B256) -> Result<Signature> {
+        Err(self.unavailable())
+    }
+}
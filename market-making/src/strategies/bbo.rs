@@ -1,17 +1,61 @@
-use crate::{Result, error::Error, strategies::Strategy};
+use crate::{
+    Result,
+    broadcaster,
+    error::Error,
+    fee::SharedFeeOracle,
+    nonce::SharedNonceScheduler,
+    strategies::{Strategy, quoting},
+};
 use alloy::providers::DynProvider;
 use fastnum::{UD64, udec64};
 use perpl_sdk::{
     abi::dex::Exchange::{ExchangeInstance, OrderDesc},
     error::DexError,
-    state::{Exchange, Order, OrderEventType, StateEvents},
-    types::{AccountId, OrderRequest, PerpetualId, RequestType},
+    state::{Exchange, Order, OrderEventType, Position, StateEvents},
+    types::{AccountId, OrderRequest, OrderType, PerpetualId, RequestType},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::OnceLock,
 };
-use std::sync::OnceLock;
 use tokio::sync::{OwnedSemaphorePermit, mpsc};
 use tracing::{debug, error, info};
 
-/// A simple market making strategy that places fixed size orders at the best bid and offer
+/// Net inventory accumulated purely from `OrderEventType::Filled` events this
+/// strategy has directly observed, independent of (and quicker to update
+/// than) the exchange's own reported `Position`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NetInventory {
+    /// `true` once the net accumulated fills are short rather than long
+    pub is_short: bool,
+    /// Magnitude of the net position
+    pub size: UD64,
+}
+
+impl NetInventory {
+    /// Fold in a fill of `size` on `side`, flipping `is_short` if the fill
+    /// crosses the position back through flat.
+    fn apply_fill(&mut self, side: OrderType, size: UD64) {
+        let same_direction = self.size.is_zero()
+            || matches!(
+                (self.is_short, side),
+                (true, OrderType::OpenShort) | (false, OrderType::OpenLong)
+            );
+
+        if same_direction {
+            self.is_short = matches!(side, OrderType::OpenShort);
+            self.size = self.size.saturating_add(size);
+        } else if size >= self.size {
+            self.is_short = matches!(side, OrderType::OpenShort);
+            self.size = size.saturating_sub(self.size);
+        } else {
+            self.size = self.size.saturating_sub(size);
+        }
+    }
+}
+
+/// A market making strategy that quotes a bid and offer around the
+/// perpetual's index price, skewed by the account's current inventory
 #[derive(Debug)]
 pub struct BboStrategy {
     /// The size of each order
@@ -20,6 +64,30 @@ pub struct BboStrategy {
     pub perpetual_id: PerpetualId,
     /// Account ID
     pub account_id: OnceLock<AccountId>,
+    /// Offset from the index price applied to each side, in basis points
+    pub offset_bps: UD64,
+    /// Scales how aggressively quotes skew to mean-revert inventory
+    pub skew_factor: UD64,
+    /// Position size at which the inventory skew saturates
+    pub max_inventory: UD64,
+    /// Cumulative filled quantity observed for each resting order id, used
+    /// to size the remaining portion of a quote after a partial fill instead
+    /// of blindly re-posting a fresh `order_size`
+    filled_qty: HashMap<u64, UD64>,
+    /// Side each tracked order id was opened on, so a `Filled` event can
+    /// still be attributed to the right side of `net_inventory` even once
+    /// the order has fully filled and left the book
+    order_sides: HashMap<u64, OrderType>,
+    /// Net inventory accumulated purely from locally observed fills, exposed
+    /// for downstream risk logic independent of `get_position`'s
+    /// exchange-reported figure
+    pub net_inventory: NetInventory,
+    /// Maximum number of resting orders this strategy will hold open at once
+    pub max_open_orders: usize,
+    /// Order ids with a `Cancel` already submitted and not yet confirmed
+    /// removed from the book, to avoid submitting a duplicate cancel while
+    /// the first one's receipt is still pending
+    pending_cancels: HashSet<u64>,
 }
 
 impl Strategy for BboStrategy {
@@ -31,6 +99,10 @@ impl Strategy for BboStrategy {
         self.perpetual_id
     }
 
+    fn max_open_orders(&self) -> usize {
+        self.max_open_orders
+    }
+
     /// On initialization this strategy cancels all existing orders
     async fn initialize(
         &mut self,
@@ -78,144 +150,296 @@ impl Strategy for BboStrategy {
         exchange: &Exchange,
         events: &[StateEvents],
         error_tx: &mpsc::Sender<DexError>,
+        nonce_scheduler: &SharedNonceScheduler,
+        fee_oracle: &SharedFeeOracle,
         permit: OwnedSemaphorePermit,
     ) {
-        if self.account_id.get().is_none() {
+        let Some(&account_id) = self.account_id.get() else {
             panic!("Strategy not initialized");
-        }
+        };
 
-        if events.is_empty() {
-            // This strategy only acts when there are block events
-            return;
-        }
+        let open_orders = self.fetch_open_orders(exchange);
+        self.reconcile_filled_qty(&open_orders);
 
-        let mut fill_event_found = false;
-        for event in events {
-            let StateEvents::Order(order_event) = event else {
-                continue;
-            };
+        // Always enforce the budget, even on a cycle with no block events,
+        // since state drift (e.g. a stray order from a previous run) can
+        // otherwise go uncorrected indefinitely
+        let mut order_descs = self.cancel_excess_orders(exchange, &open_orders);
+
+        if events.is_empty() {
+            // No block events: nothing else for this strategy to react to
+            if order_descs.is_empty() {
+                return;
+            }
+        } else {
+            let mut fill_event_found = false;
+            for event in events {
+                let StateEvents::Order(order_event) = event else {
+                    continue;
+                };
+
+                if order_event.account_id != account_id
+                    || order_event.perpetual_id != self.perpetual_id
+                {
+                    continue;
+                }
+
+                let OrderEventType::Filled { size, .. } = order_event.r#type else {
+                    continue;
+                };
 
-            if matches!(order_event.r#type, OrderEventType::Filled { .. }) {
                 fill_event_found = true;
-                break;
+
+                let cumulative = self
+                    .filled_qty
+                    .entry(order_event.order_id)
+                    .or_insert(UD64::ZERO);
+                *cumulative = cumulative.saturating_add(size);
+
+                if let Some(&side) = self.order_sides.get(&order_event.order_id) {
+                    self.net_inventory.apply_fill(side, size);
+                }
             }
-        }
 
-        if !fill_event_found {
-            // No fills, no action
-            return;
-        }
+            // Sides of orders that have left the book are forgotten only now,
+            // after this batch's fills on them have been attributed
+            let live_ids: HashSet<u64> = open_orders.iter().map(|o| o.order_id()).collect();
+            self.order_sides.retain(|id, _| live_ids.contains(id));
 
-        let (best_bid, best_ask) = self.get_bbo(exchange);
-        let Some(best_bid) = best_bid else {
-            info!("No best bid available, skipping order placement");
-            return;
-        };
+            if !fill_event_found && order_descs.is_empty() {
+                // No fills, no action
+                return;
+            }
 
-        let Some(best_ask) = best_ask else {
-            info!("No best ask available, skipping order placement");
-            return;
-        };
+            if fill_event_found {
+                debug!(net_inventory = ?self.net_inventory, "Observed fill(s), re-quoting partially-filled side(s)");
+            }
+        }
 
-        let open_orders = self.fetch_open_orders(exchange);
+        let index_price = self.get_index_price(exchange);
+        let position = self.get_position(exchange);
+
+        let quote = match quoting::fair_value_quote(
+            index_price,
+            position,
+            self.offset_bps,
+            self.skew_factor,
+            self.max_inventory,
+        ) {
+            Ok(quote) => quote,
+            Err(error) => {
+                error!(%error, "Failed to compute fair-value quote, skipping this cycle");
+                return;
+            }
+        };
 
         // This strategy only places one bid and one ask order at a time
         let bid = open_orders
             .iter()
-            .find(|o| o.r#type().side() == perpl_sdk::types::OrderSide::Bid);
-
-        let mut order_descs = Vec::new();
+            .find(|o| o.r#type() == OrderType::OpenLong);
 
         if let Some(bid) = bid {
-            if bid.price() < best_bid {
-                let update_bid = self.update_order(exchange, bid, best_bid);
-                order_descs.push(update_bid);
+            if self.pending_cancels.contains(&bid.order_id()) {
+                // Already being cancelled to stay within budget; leave it alone
+            } else {
+                self.order_sides.insert(bid.order_id(), OrderType::OpenLong);
+                let remaining = self.remaining_order_size(bid.order_id());
+
+                if bid.price() != quote.bid || bid.size() != remaining {
+                    let update_bid = self.update_order(exchange, bid, quote.bid, remaining);
+                    order_descs.push(update_bid);
+                }
             }
         } else {
-            let place_bid = self.place_order(exchange, RequestType::OpenLong, best_bid);
+            let place_bid =
+                self.place_order(exchange, RequestType::OpenLong, quote.bid, self.order_size);
             order_descs.push(place_bid);
         }
 
         let ask = open_orders
             .iter()
-            .find(|o| o.r#type().side() == perpl_sdk::types::OrderSide::Ask);
+            .find(|o| o.r#type() == OrderType::OpenShort);
 
         if let Some(ask) = ask {
-            if ask.price() > best_ask {
-                let update_ask = self.update_order(exchange, ask, best_ask);
-                order_descs.push(update_ask);
+            if self.pending_cancels.contains(&ask.order_id()) {
+                // Already being cancelled to stay within budget; leave it alone
+            } else {
+                self.order_sides.insert(ask.order_id(), OrderType::OpenShort);
+                let remaining = self.remaining_order_size(ask.order_id());
+
+                if ask.price() != quote.ask || ask.size() != remaining {
+                    let update_ask = self.update_order(exchange, ask, quote.ask, remaining);
+                    order_descs.push(update_ask);
+                }
             }
         } else {
-            let place_ask = self.place_order(exchange, RequestType::OpenShort, best_ask);
+            let place_ask =
+                self.place_order(exchange, RequestType::OpenShort, quote.ask, self.order_size);
             order_descs.push(place_ask);
         }
 
-        let builder = instance.execOpsAndOrders(vec![], order_descs, true);
-
-        debug!(?builder, "Submitting initial bbo orders transaction");
-
-        match builder.send().await.map_err(DexError::from) {
-            Ok(res) => {
-                let error_tx = error_tx.clone();
-                tokio::spawn(async move {
-                    match res.get_receipt().await.map_err(DexError::from) {
-                        Ok(tx) => {
-                            debug!(?tx, "Bbo orders transaction complete");
-                        }
-                        Err(error) => {
-                            error!(%error, "Error executing bbo orders transaction");
-                            error_tx
-                                .send(error)
-                                .await
-                                .expect("Failed to send error to channel");
-                        }
-                    }
-
-                    drop(permit);
-                });
-            }
-            Err(error) => {
-                error!(%error, "Error sending transaction");
-                error_tx.send(error).await.expect("Failed to send error");
-            }
+        if order_descs.is_empty() {
+            return;
         }
+
+        debug!(order_count = order_descs.len(), "Submitting bbo orders transaction");
+
+        let task_instance = instance.clone();
+        let task_fee_oracle = fee_oracle.clone();
+        let task_nonce_scheduler = nonce_scheduler.clone();
+        let task_error_tx = error_tx.clone();
+        tokio::spawn(async move {
+            broadcaster::submit_with_fee_retry(
+                &task_instance,
+                &task_fee_oracle,
+                &task_nonce_scheduler,
+                &task_error_tx,
+                permit,
+                order_descs,
+                true,
+                "bbo",
+            )
+            .await;
+        });
     }
 }
 
 impl BboStrategy {
-    pub fn new(order_size: UD64, perpetual_id: PerpetualId) -> Self {
+    pub fn new(
+        order_size: UD64,
+        perpetual_id: PerpetualId,
+        offset_bps: UD64,
+        skew_factor: UD64,
+        max_inventory: UD64,
+        max_open_orders: usize,
+    ) -> Self {
         Self {
             order_size,
             perpetual_id,
             account_id: OnceLock::new(),
+            offset_bps,
+            skew_factor,
+            max_inventory,
+            filled_qty: HashMap::new(),
+            order_sides: HashMap::new(),
+            net_inventory: NetInventory::default(),
+            max_open_orders,
+            pending_cancels: HashSet::new(),
         }
     }
 
-    fn cancel_all_orders(&self, exchange: &Exchange) -> Vec<OrderDesc> {
+    /// Reconcile cached per-order fill totals against the current book state.
+    /// An order id can be cancelled and its id reused for an unrelated order
+    /// between blocks; if the book shows more size resting under an id than
+    /// our cached total leaves room for, this can't be the same order, so
+    /// the stale entry is dropped rather than trusted.
+    fn reconcile_filled_qty(&mut self, open_orders: &[&Order]) {
+        for order in open_orders {
+            let order_id = order.order_id();
+            if let Some(&cumulative) = self.filled_qty.get(&order_id) {
+                let expected_remaining = self.order_size.saturating_sub(cumulative);
+                if order.size() > expected_remaining {
+                    debug!(order_id, "Order id reused since last cycle, resetting fill tracking");
+                    self.filled_qty.remove(&order_id);
+                    self.order_sides.remove(&order_id);
+                }
+            }
+        }
+
+        let live_ids: HashSet<u64> = open_orders.iter().map(|o| o.order_id()).collect();
+        self.filled_qty.retain(|id, _| live_ids.contains(id));
+
+        // A cancel is confirmed (or made moot by a full fill) once its order
+        // id is no longer live in the book, freeing its budget slot
+        self.pending_cancels.retain(|id| live_ids.contains(id));
+    }
+
+    /// Remaining size to quote for a tracked order id: the configured
+    /// `order_size` less whatever has already been confirmed filled.
+    fn remaining_order_size(&self, order_id: u64) -> UD64 {
+        let filled = self.filled_qty.get(&order_id).copied().unwrap_or(UD64::ZERO);
+        self.order_size.saturating_sub(filled)
+    }
+
+    /// Cancel whatever this account's oldest resting orders are beyond
+    /// `max_open_orders`, skipping any order id that already has a cancel in
+    /// flight (tracked in `pending_cancels`) or that local bookkeeping shows
+    /// is already fully filled, so the same order is never cancelled twice.
+    ///
+    /// `open_orders` comes from `fetch_open_orders`, which iterates the book's
+    /// order map in arbitrary order, so "oldest" is determined here by
+    /// sorting on `order_id` — assigned sequentially by the exchange, so the
+    /// lowest id among an account's resting orders is the oldest one.
+    fn cancel_excess_orders(&mut self, exchange: &Exchange, open_orders: &[&Order]) -> Vec<OrderDesc> {
+        if open_orders.len() <= self.max_open_orders {
+            return Vec::new();
+        }
+
+        let mut open_orders: Vec<&Order> = open_orders.to_vec();
+        open_orders.sort_by_key(|order| order.order_id());
+
+        let excess = open_orders.len() - self.max_open_orders;
+        let mut order_descs = Vec::new();
+
+        for order in open_orders.iter().take(excess) {
+            let order_id = order.order_id();
+
+            if self.pending_cancels.contains(&order_id) {
+                continue;
+            }
+
+            if self
+                .filled_qty
+                .get(&order_id)
+                .is_some_and(|&filled| filled >= self.order_size)
+            {
+                // Already fully filled; nothing left to cancel
+                continue;
+            }
+
+            info!(order_id, "Cancelling order to stay within max_open_orders budget");
+            order_descs.push(self.cancel_order(exchange, order_id));
+            self.pending_cancels.insert(order_id);
+        }
+
+        order_descs
+    }
+
+    fn cancel_order(&self, exchange: &Exchange, order_id: u64) -> OrderDesc {
+        let request = OrderRequest::new(
+            0,
+            self.perpetual_id,
+            RequestType::Cancel,
+            Some(order_id),
+            udec64!(0),
+            udec64!(0),
+            None,
+            false,
+            false,
+            false,
+            None,
+            udec64!(0),
+            None,
+            None,
+        );
+
+        request.prepare(exchange)
+    }
+
+    fn cancel_all_orders(&mut self, exchange: &Exchange) -> Vec<OrderDesc> {
         let open_orders = self.fetch_open_orders(exchange);
         let mut order_descs = vec![];
 
         for order in open_orders {
-            info!(order_id = %order.order_id(), "Cancelling order");
-            let request = OrderRequest::new(
-                0,
-                self.perpetual_id,
-                RequestType::Cancel,
-                Some(order.order_id()),
-                udec64!(0),
-                udec64!(0),
-                None,
-                false,
-                false,
-                false,
-                None,
-                udec64!(0),
-                None,
-                None,
-            );
+            let order_id = order.order_id();
+
+            if self.pending_cancels.contains(&order_id) {
+                continue;
+            }
 
-            let request = request.prepare(exchange);
-            order_descs.push(request);
+            info!(order_id = %order_id, "Cancelling order");
+            order_descs.push(self.cancel_order(exchange, order_id));
+            self.pending_cancels.insert(order_id);
         }
 
         order_descs
@@ -238,25 +462,40 @@ impl BboStrategy {
             .collect()
     }
 
-    fn get_bbo(&self, exchange: &Exchange) -> (Option<UD64>, Option<UD64>) {
+    fn get_index_price(&self, exchange: &Exchange) -> UD64 {
         let perpetual = exchange
             .perpetuals()
             .get(&self.perpetual_id)
             .expect("perpetual must exist");
 
-        let best_bid_price = perpetual.l3_book().best_bid().map(|(price, _)| price);
-        let best_ask_price = perpetual.l3_book().best_ask().map(|(price, _)| price);
-        (best_bid_price, best_ask_price)
+        perpetual.index_price()
     }
 
-    fn place_order(&self, exchange: &Exchange, order_type: RequestType, price: UD64) -> OrderDesc {
+    fn get_position<'a>(&self, exchange: &'a Exchange) -> Option<&'a Position> {
+        let account_id = self.account_id.get().expect("Strategy not initialized");
+
+        exchange
+            .accounts()
+            .get(account_id)
+            .expect("Account should exist in exchange state")
+            .positions()
+            .get(&self.perpetual_id)
+    }
+
+    fn place_order(
+        &self,
+        exchange: &Exchange,
+        order_type: RequestType,
+        price: UD64,
+        size: UD64,
+    ) -> OrderDesc {
         let request = OrderRequest::new(
             0,
             self.perpetual_id,
             order_type,
             None,
             price,
-            self.order_size,
+            size,
             None,
             // post_only since we want to provide liquidity not take it
             true,
@@ -272,14 +511,14 @@ impl BboStrategy {
         request.prepare(exchange)
     }
 
-    fn update_order(&self, exchange: &Exchange, order: &Order, price: UD64) -> OrderDesc {
+    fn update_order(&self, exchange: &Exchange, order: &Order, price: UD64, size: UD64) -> OrderDesc {
         let request = OrderRequest::new(
             0,
             self.perpetual_id,
             RequestType::Change,
             Some(order.order_id()),
             price,
-            self.order_size,
+            size,
             None,
             // post_only since we want to provide liquidity not take it
             true,
@@ -1,6 +1,11 @@
 use crate::{
     Result,
-    strategies::{bbo::BboStrategy, spread::SpreadStrategy, taker::TakerStrategy},
+    fee::SharedFeeOracle,
+    nonce::SharedNonceScheduler,
+    strategies::{
+        bbo::BboStrategy, pegged_spread::PeggedSpreadStrategy, rollover::RolloverStrategy,
+        spread::SpreadStrategy, taker::TakerStrategy,
+    },
 };
 use alloy::providers::DynProvider;
 use dex_sdk::{
@@ -12,6 +17,10 @@ use dex_sdk::{
 use tokio::sync::{OwnedSemaphorePermit, mpsc};
 
 pub mod bbo;
+pub mod pegged_spread;
+pub mod quoting;
+pub mod reconcile;
+pub mod rollover;
 pub mod spread;
 pub mod taker;
 
@@ -20,6 +29,12 @@ pub trait Strategy {
 
     fn perpetual_id(&self) -> PerpetualId;
 
+    /// Maximum number of resting orders this strategy will hold open at
+    /// once; `usize::MAX` (the default) leaves it unbounded.
+    fn max_open_orders(&self) -> usize {
+        usize::MAX
+    }
+
     /// Run the initial setup for the strategy
     fn initialize(
         &mut self,
@@ -34,6 +49,8 @@ pub trait Strategy {
         exchange: &Exchange,
         events: &[StateEvents],
         error_tx: &mpsc::Sender<DexError>,
+        nonce_scheduler: &SharedNonceScheduler,
+        fee_oracle: &SharedFeeOracle,
         permit: OwnedSemaphorePermit,
     ) -> impl Future<Output = ()>;
 }
@@ -41,6 +58,8 @@ pub trait Strategy {
 #[derive(Debug)]
 pub enum StrategyType {
     Bbo(BboStrategy),
+    PeggedSpread(PeggedSpreadStrategy),
+    Rollover(RolloverStrategy),
     Spread(SpreadStrategy),
     Taker(TakerStrategy),
 }
@@ -49,6 +68,8 @@ impl Strategy for StrategyType {
     fn name(&self) -> &'static str {
         match self {
             StrategyType::Bbo(strategy) => strategy.name(),
+            StrategyType::PeggedSpread(strategy) => strategy.name(),
+            StrategyType::Rollover(strategy) => strategy.name(),
             StrategyType::Spread(strategy) => strategy.name(),
             StrategyType::Taker(strategy) => strategy.name(),
         }
@@ -57,11 +78,23 @@ impl Strategy for StrategyType {
     fn perpetual_id(&self) -> PerpetualId {
         match self {
             StrategyType::Bbo(strategy) => strategy.perpetual_id(),
+            StrategyType::PeggedSpread(strategy) => strategy.perpetual_id(),
+            StrategyType::Rollover(strategy) => strategy.perpetual_id(),
             StrategyType::Spread(strategy) => strategy.perpetual_id(),
             StrategyType::Taker(strategy) => strategy.perpetual_id(),
         }
     }
 
+    fn max_open_orders(&self) -> usize {
+        match self {
+            StrategyType::Bbo(strategy) => strategy.max_open_orders(),
+            StrategyType::PeggedSpread(strategy) => strategy.max_open_orders(),
+            StrategyType::Rollover(strategy) => strategy.max_open_orders(),
+            StrategyType::Spread(strategy) => strategy.max_open_orders(),
+            StrategyType::Taker(strategy) => strategy.max_open_orders(),
+        }
+    }
+
     async fn initialize(
         &mut self,
         instance: &ExchangeInstance<DynProvider>,
@@ -69,6 +102,8 @@ impl Strategy for StrategyType {
     ) -> Result<()> {
         match self {
             StrategyType::Bbo(strategy) => strategy.initialize(instance, exchange).await,
+            StrategyType::PeggedSpread(strategy) => strategy.initialize(instance, exchange).await,
+            StrategyType::Rollover(strategy) => strategy.initialize(instance, exchange).await,
             StrategyType::Spread(strategy) => strategy.initialize(instance, exchange).await,
             StrategyType::Taker(strategy) => strategy.initialize(instance, exchange).await,
         }
@@ -80,22 +115,34 @@ impl Strategy for StrategyType {
         exchange: &Exchange,
         events: &[StateEvents],
         error_tx: &mpsc::Sender<DexError>,
+        nonce_scheduler: &SharedNonceScheduler,
+        fee_oracle: &SharedFeeOracle,
         permit: OwnedSemaphorePermit,
     ) {
         match self {
             StrategyType::Bbo(strategy) => {
                 strategy
-                    .execute(instance, exchange, events, error_tx, permit)
+                    .execute(instance, exchange, events, error_tx, nonce_scheduler, fee_oracle, permit)
+                    .await
+            }
+            StrategyType::PeggedSpread(strategy) => {
+                strategy
+                    .execute(instance, exchange, events, error_tx, nonce_scheduler, fee_oracle, permit)
+                    .await
+            }
+            StrategyType::Rollover(strategy) => {
+                strategy
+                    .execute(instance, exchange, events, error_tx, nonce_scheduler, fee_oracle, permit)
                     .await
             }
             StrategyType::Spread(strategy) => {
                 strategy
-                    .execute(instance, exchange, events, error_tx, permit)
+                    .execute(instance, exchange, events, error_tx, nonce_scheduler, fee_oracle, permit)
                     .await
             }
             StrategyType::Taker(strategy) => {
                 strategy
-                    .execute(instance, exchange, events, error_tx, permit)
+                    .execute(instance, exchange, events, error_tx, nonce_scheduler, fee_oracle, permit)
                     .await
             }
         }
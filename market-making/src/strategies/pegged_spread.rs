@@ -0,0 +1,373 @@
+use crate::{
+    Result,
+    broadcaster,
+    error::Error,
+    fee::SharedFeeOracle,
+    nonce::SharedNonceScheduler,
+    strategies::{Strategy, quoting},
+};
+use alloy::providers::DynProvider;
+use fastnum::{UD64, udec64};
+use perpl_sdk::{
+    abi::dex::Exchange::{ExchangeInstance, OrderDesc},
+    error::DexError,
+    state::{Exchange, StateEvents},
+    types::{AccountId, OrderRequest, OrderType, PerpetualId, RequestType},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::OnceLock,
+};
+use tokio::sync::{OwnedSemaphorePermit, mpsc};
+use tracing::{debug, info, trace};
+
+/// A market making strategy intended to peg its resting orders to the
+/// perpetual's oracle/mark price as a signed offset, so the exchange
+/// re-prices them automatically as the oracle moves instead of the strategy
+/// sending a `Change` transaction on every tick.
+///
+/// This tree's `OrderRequest`/`OrderDesc` surface has no peg-offset field to
+/// submit against, so there is no on-chain pegging happening here yet; what
+/// this strategy delivers with the SDK surface available is the closest
+/// approximation: it only touches the chain when the set of resting levels
+/// actually changes (a fill, a restart) or when `quote_ttl_blocks`/
+/// `max_quote_distance_bps` mark a level as having drifted too far from the
+/// oracle to leave resting, the same TTL/distance guard `SpreadStrategy`
+/// uses to keep its own quotes from going stale.
+#[derive(Debug)]
+pub struct PeggedSpreadStrategy {
+    /// Number of orders to place on each side
+    pub orders_per_side: usize,
+    /// The size of each order
+    pub order_size: UD64,
+    /// The perpetual ID to trade on
+    pub perpetual_id: PerpetualId,
+    /// Offset applied to the innermost order on each side, in basis points
+    pub offset_bps: UD64,
+    /// Max matches per order
+    pub max_matches_per_order: Option<u32>,
+    /// Leverage for orders
+    pub leverage: UD64,
+    /// Optional absolute price cap: a pegged bid is never submitted above
+    /// this price, so a fast upward oracle jump can't turn a resting bid
+    /// into a taker before the strategy has a chance to react
+    pub peg_price_cap: Option<UD64>,
+    /// Maximum number of blocks a resting order is allowed to go unchanged
+    /// before it's cancelled and re-quoted, regardless of how close it still
+    /// is to the current mark
+    pub quote_ttl_blocks: u64,
+    /// Maximum distance, in basis points of the index price, a resting order
+    /// may sit from the current mark before it's cancelled and re-quoted
+    pub max_quote_distance_bps: UD64,
+    /// Account ID
+    pub account_id: OnceLock<AccountId>,
+    /// Number of resting levels observed on each side last cycle; re-quote
+    /// only fires when this no longer matches `orders_per_side`, or when a
+    /// level is cancelled for drifting past its TTL/distance
+    last_seen_levels: Option<(usize, usize)>,
+    /// Block each currently-resting order id was first observed at its
+    /// current price, used to age out stale quotes
+    resting_since: HashMap<u64, u64>,
+    /// Order ids with a TTL/distance `Cancel` already submitted and not yet
+    /// confirmed removed from the book, to avoid submitting a duplicate
+    /// cancel while the first one's receipt is still pending
+    pending_cancels: HashSet<u64>,
+}
+
+impl Strategy for PeggedSpreadStrategy {
+    fn name(&self) -> &'static str {
+        "PeggedSpread"
+    }
+
+    fn perpetual_id(&self) -> PerpetualId {
+        self.perpetual_id
+    }
+
+    /// On initialization this strategy records the account id and checks the
+    /// perpetual exists; orders already resting are not cancelled
+    async fn initialize(
+        &mut self,
+        _instance: &ExchangeInstance<DynProvider>,
+        exchange: &Exchange,
+    ) -> Result<()> {
+        let accounts = exchange.accounts();
+        if accounts.is_empty() {
+            return Err(Error::NoAccountFoundForStrategy);
+        }
+
+        if accounts.len() > 1 {
+            return Err(Error::TooManyAccountsForStrategy);
+        }
+
+        let account_id = *accounts.keys().next().unwrap();
+
+        self.account_id
+            .set(account_id)
+            .map_err(|_| Error::AccountIdAlreadySet)?;
+
+        if !exchange.perpetuals().contains_key(&self.perpetual_id) {
+            return Err(Error::PerpetualNotFoundInExchangeState(self.perpetual_id));
+        }
+
+        info!(%account_id, "PeggedSpread Strategy initialized");
+
+        Ok(())
+    }
+
+    async fn execute(
+        &mut self,
+        instance: &ExchangeInstance<DynProvider>,
+        exchange: &Exchange,
+        _events: &[StateEvents],
+        error_tx: &mpsc::Sender<DexError>,
+        nonce_scheduler: &SharedNonceScheduler,
+        fee_oracle: &SharedFeeOracle,
+        permit: OwnedSemaphorePermit,
+    ) {
+        let Some(_account_id) = self.account_id.get().copied() else {
+            panic!("Strategy not initialized");
+        };
+
+        let order_descs = self.process_orders(exchange);
+        if order_descs.is_empty() {
+            return;
+        }
+
+        trace!(order_count = order_descs.len(), "Submitting pegged spread orders transaction");
+
+        let task_instance = instance.clone();
+        let task_fee_oracle = fee_oracle.clone();
+        let task_nonce_scheduler = nonce_scheduler.clone();
+        let task_error_tx = error_tx.clone();
+        tokio::spawn(async move {
+            broadcaster::submit_with_fee_retry(
+                &task_instance,
+                &task_fee_oracle,
+                &task_nonce_scheduler,
+                &task_error_tx,
+                permit,
+                order_descs,
+                false,
+                "pegged_spread",
+            )
+            .await;
+        });
+    }
+}
+
+impl PeggedSpreadStrategy {
+    pub fn new(
+        orders_per_side: usize,
+        order_size: UD64,
+        perpetual_id: PerpetualId,
+        offset_bps: UD64,
+        max_matches_per_order: Option<u32>,
+        leverage: UD64,
+        peg_price_cap: Option<UD64>,
+        quote_ttl_blocks: u64,
+        max_quote_distance_bps: UD64,
+    ) -> Self {
+        Self {
+            orders_per_side,
+            order_size,
+            perpetual_id,
+            offset_bps,
+            max_matches_per_order,
+            leverage,
+            peg_price_cap,
+            quote_ttl_blocks,
+            max_quote_distance_bps,
+            account_id: OnceLock::new(),
+            last_seen_levels: None,
+            resting_since: HashMap::new(),
+            pending_cancels: HashSet::new(),
+        }
+    }
+
+    /// Cancel any level that's drifted past its TTL or distance from the
+    /// mark, then top up every target level that has no live order sitting
+    /// at its price (the ones just cancelled, plus any never placed) rather
+    /// than just appending enough orders to reach `orders_per_side`, so
+    /// cancelling a non-outermost level re-quotes that same level instead of
+    /// duplicating the outermost one; a level still within its TTL and
+    /// distance budget is left resting untouched.
+    fn process_orders(&mut self, exchange: &Exchange) -> Vec<OrderDesc> {
+        let index_price = self.get_index_price(exchange);
+        let open_orders = self.fetch_open_orders(exchange);
+        let current_block = exchange.instant().block_number();
+
+        let live_ids: HashSet<u64> = open_orders.iter().map(|o| o.order_id()).collect();
+        self.resting_since.retain(|order_id, _| live_ids.contains(order_id));
+        self.pending_cancels.retain(|order_id| live_ids.contains(order_id));
+
+        let mut order_descs = Vec::new();
+        let mut stale_ids = HashSet::new();
+
+        for order in &open_orders {
+            let order_id = order.order_id();
+
+            if self.pending_cancels.contains(&order_id) {
+                stale_ids.insert(order_id);
+                continue;
+            }
+
+            let since = *self.resting_since.entry(order_id).or_insert(current_block);
+            let age_blocks = current_block.saturating_sub(since);
+            let distance_bps = quoting::quote_distance_bps(order.price(), index_price);
+
+            if age_blocks > self.quote_ttl_blocks || distance_bps > self.max_quote_distance_bps {
+                debug!(
+                    order_id,
+                    age_blocks,
+                    %distance_bps,
+                    "Cancelling pegged quote past its TTL or distance from the oracle"
+                );
+                order_descs.push(self.cancel_order(exchange, order_id));
+                stale_ids.insert(order_id);
+                self.resting_since.remove(&order_id);
+                self.pending_cancels.insert(order_id);
+            }
+        }
+
+        let live_bid_prices: HashSet<UD64> = open_orders
+            .iter()
+            .filter(|o| o.r#type() == OrderType::OpenLong && !stale_ids.contains(&o.order_id()))
+            .map(|o| o.price())
+            .collect();
+        let live_ask_prices: HashSet<UD64> = open_orders
+            .iter()
+            .filter(|o| o.r#type() == OrderType::OpenShort && !stale_ids.contains(&o.order_id()))
+            .map(|o| o.price())
+            .collect();
+        let bid_count = live_bid_prices.len();
+        let ask_count = live_ask_prices.len();
+
+        if stale_ids.is_empty()
+            && self.last_seen_levels == Some((bid_count, ask_count))
+            && bid_count >= self.orders_per_side
+            && ask_count >= self.orders_per_side
+        {
+            return Vec::new();
+        }
+
+        self.last_seen_levels = Some((bid_count, ask_count));
+
+        info!(
+            bid_count,
+            ask_count,
+            orders_per_side = self.orders_per_side,
+            "Pegged level count changed, topping up missing levels"
+        );
+
+        // Top up the *specific* levels missing a live order, not just
+        // however many are short of `orders_per_side` — a non-outermost
+        // level cancelled as stale leaves a gap at its own price, and
+        // counting from `bid_count`/`ask_count` would instead re-place it at
+        // the outermost level, duplicating that price and leaving the real
+        // gap unfilled.
+        for level in 0..self.orders_per_side {
+            let price = self.pegged_price(index_price, level, RequestType::OpenLong);
+            if !live_bid_prices.contains(&price) {
+                order_descs.push(self.place_order(exchange, RequestType::OpenLong, price));
+            }
+        }
+
+        for level in 0..self.orders_per_side {
+            let price = self.pegged_price(index_price, level, RequestType::OpenShort);
+            if !live_ask_prices.contains(&price) {
+                order_descs.push(self.place_order(exchange, RequestType::OpenShort, price));
+            }
+        }
+
+        order_descs
+    }
+
+    /// `mark · (1 ∓ offset_bps/10_000 · (i+1))`, clamped by `peg_price_cap` so
+    /// a bid never crosses into a taker during a fast oracle jump.
+    fn pegged_price(&self, index_price: UD64, level: usize, order_type: RequestType) -> UD64 {
+        let step = self.offset_bps.checked_div(udec64!(10_000)).unwrap_or(UD64::ZERO)
+            * UD64::from(level + 1);
+        let offset = index_price.checked_mul(step).unwrap_or(UD64::ZERO);
+
+        let price = match order_type {
+            RequestType::OpenLong => index_price.saturating_sub(offset),
+            _ => index_price.saturating_add(offset),
+        };
+
+        match (order_type, self.peg_price_cap) {
+            (RequestType::OpenLong, Some(cap)) if price > cap => cap,
+            _ => price,
+        }
+    }
+
+    fn fetch_open_orders<'a>(&self, exchange: &'a Exchange) -> Vec<&'a perpl_sdk::state::Order> {
+        let Some(account_id) = self.account_id.get() else {
+            panic!("Strategy not initialized");
+        };
+
+        exchange
+            .perpetuals()
+            .get(&self.perpetual_id)
+            .unwrap()
+            .l3_book()
+            .all_orders()
+            .values()
+            .filter(|o| o.account_id() == *account_id)
+            .map(|o| &*(*o))
+            .collect()
+    }
+
+    fn get_index_price(&self, exchange: &Exchange) -> UD64 {
+        let perpetual = exchange
+            .perpetuals()
+            .get(&self.perpetual_id)
+            .expect("perpetual must exist");
+
+        perpetual.index_price()
+    }
+
+    fn cancel_order(&self, exchange: &Exchange, order_id: u64) -> OrderDesc {
+        info!(order_id, "Cancelling pegged order");
+        let request = OrderRequest::new(
+            order_id,
+            self.perpetual_id,
+            RequestType::Cancel,
+            Some(order_id),
+            udec64!(0),
+            udec64!(0),
+            None,
+            false,
+            false,
+            false,
+            None,
+            udec64!(0),
+            None,
+            None,
+        );
+
+        request.prepare(exchange)
+    }
+
+    fn place_order(&self, exchange: &Exchange, order_type: RequestType, price: UD64) -> OrderDesc {
+        info!(?order_type, %price, "Placing pegged order");
+        let request = OrderRequest::new(
+            0,
+            self.perpetual_id,
+            order_type,
+            None,
+            price,
+            self.order_size,
+            None,
+            // post_only since we want to provide liquidity not take it
+            true,
+            false,
+            false,
+            self.max_matches_per_order,
+            self.leverage,
+            None,
+            None,
+        );
+
+        request.prepare(exchange)
+    }
+}
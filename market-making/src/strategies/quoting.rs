@@ -0,0 +1,275 @@
+use crate::{Result, error::Error};
+use dex_sdk::state::{Position, PositionType};
+use fastnum::{UD64, udec64};
+
+/// Avellaneda–Stoikov inventory-aware quoting state. Holds the parameters of
+/// the model plus the running volatility estimate, which is updated each
+/// cycle from successive mark prices rather than recomputed from scratch.
+#[derive(Debug, Clone)]
+pub struct InventoryModel {
+    /// Risk aversion parameter (γ): how strongly inventory shifts the reservation price
+    pub risk_aversion: UD64,
+    /// Order arrival intensity parameter (k) used in the optimal half-spread
+    pub order_arrival_rate: UD64,
+    /// Fixed stand-in for the horizon term `(T - t)`; a perpetual never expires,
+    /// so this is a constant tuning knob rather than a real time-to-expiry
+    pub horizon: UD64,
+    /// Smoothing factor for the EWMA variance estimate, in `(0, 1]`
+    pub volatility_ewma_alpha: UD64,
+    last_mark_price: Option<UD64>,
+    /// Current EWMA estimate of σ², the squared-return variance per cycle
+    variance: UD64,
+}
+
+impl InventoryModel {
+    pub fn new(
+        risk_aversion: UD64,
+        order_arrival_rate: UD64,
+        horizon: UD64,
+        volatility_ewma_alpha: UD64,
+    ) -> Self {
+        Self {
+            risk_aversion,
+            order_arrival_rate,
+            horizon,
+            volatility_ewma_alpha,
+            last_mark_price: None,
+            variance: UD64::ZERO,
+        }
+    }
+
+    /// Fold the latest mark price into the EWMA variance estimate. A no-op on
+    /// the first call, since a return needs two samples.
+    fn observe_mark_price(&mut self, mark_price: UD64) {
+        if let Some(last) = self.last_mark_price.filter(|last| !last.is_zero()) {
+            let abs_return = if mark_price >= last {
+                mark_price.saturating_sub(last)
+            } else {
+                last.saturating_sub(mark_price)
+            };
+
+            if let Some(sample_variance) = abs_return
+                .checked_div(last)
+                .and_then(|r| r.checked_mul(r))
+            {
+                let decayed_old = self
+                    .variance
+                    .checked_mul(UD64::ONE.saturating_sub(self.volatility_ewma_alpha));
+                let weighted_new = sample_variance.checked_mul(self.volatility_ewma_alpha);
+
+                if let (Some(decayed_old), Some(weighted_new)) = (decayed_old, weighted_new) {
+                    self.variance = decayed_old.saturating_add(weighted_new);
+                }
+            }
+        }
+
+        self.last_mark_price = Some(mark_price);
+    }
+}
+
+/// `ln(1 + x)` via a two-term Taylor expansion. `UD64` has no transcendental
+/// functions, and `x = γ/k` is expected to be small, so this stands in for a
+/// true natural log rather than pulling in a float round-trip.
+fn ln_1p_approx(x: UD64) -> UD64 {
+    let x_sq_over_2 = x.checked_mul(x).and_then(|v| v.checked_div(udec64!(2)));
+
+    match x_sq_over_2 {
+        Some(x_sq_over_2) => x.saturating_sub(x_sq_over_2),
+        None => x,
+    }
+}
+
+/// A bid/ask pair quoted relative to a reference (index) price.
+#[derive(Debug, Clone, Copy)]
+pub struct FairValueQuote {
+    pub bid: UD64,
+    pub ask: UD64,
+}
+
+/// Compute a bid/ask pair anchored to `index_price`, widened by `offset_bps`
+/// and shifted by an inventory skew that mean-reverts the account's current
+/// `position` back towards flat as its size approaches `max_inventory`.
+///
+/// Mirrors the rate-conversion approach used elsewhere in the SDK: every
+/// division is checked and returns a `QuoteOverflow` error rather than
+/// panicking on overflow.
+pub fn fair_value_quote(
+    index_price: UD64,
+    position: Option<&Position>,
+    offset_bps: UD64,
+    skew_factor: UD64,
+    max_inventory: UD64,
+) -> Result<FairValueQuote> {
+    if max_inventory.is_zero() {
+        return Err(Error::QuoteOverflow);
+    }
+
+    let spread = index_price
+        .checked_mul(offset_bps)
+        .and_then(|v| v.checked_div(udec64!(10_000)))
+        .ok_or(Error::QuoteOverflow)?;
+
+    let skew_magnitude = match position {
+        Some(pos) => index_price
+            .checked_mul(pos.size())
+            .and_then(|v| v.checked_mul(skew_factor))
+            .and_then(|v| v.checked_div(max_inventory))
+            .ok_or(Error::QuoteOverflow)?,
+        None => UD64::ZERO,
+    };
+
+    // A long position skews quotes down to encourage selling it off; a short
+    // position skews them up to encourage buying it back.
+    let is_long = position.is_some_and(|pos| pos.r#type() == PositionType::Long);
+
+    let (bid, ask) = if is_long {
+        (
+            index_price.saturating_sub(spread).saturating_sub(skew_magnitude),
+            index_price.saturating_add(spread).saturating_sub(skew_magnitude),
+        )
+    } else {
+        (
+            index_price.saturating_sub(spread).saturating_add(skew_magnitude),
+            index_price.saturating_add(spread).saturating_add(skew_magnitude),
+        )
+    };
+
+    Ok(FairValueQuote { bid, ask })
+}
+
+/// Compute a bid/ask pair using the Avellaneda–Stoikov optimal market-making
+/// formulas instead of a fixed symmetric offset: the reservation price
+/// `r = mark − q·γ·σ²·(T−t)` shifts quotes away from the side the account is
+/// already positioned on, and the optimal half-spread
+/// `δ = ½·γ·σ²·(T−t) + (1/γ)·ln(1 + γ/k)` sets how far bid/ask sit from `r`.
+///
+/// `model` tracks the EWMA volatility estimate across calls, so it must be
+/// fed every cycle's mark price even when the strategy ends up flat.
+pub fn avellaneda_stoikov_quote(
+    model: &mut InventoryModel,
+    mark_price: UD64,
+    position: Option<&Position>,
+    max_inventory: UD64,
+) -> Result<FairValueQuote> {
+    model.observe_mark_price(mark_price);
+
+    let q = position.map(Position::size).unwrap_or(UD64::ZERO);
+    let is_long = position.is_some_and(|pos| pos.r#type() == PositionType::Long);
+
+    // Inventory has breached the configured max: stop quoting the side that
+    // would grow it further and only offer to unwind.
+    if !max_inventory.is_zero() && q >= max_inventory {
+        return Ok(if is_long {
+            FairValueQuote { bid: UD64::ZERO, ask: mark_price }
+        } else {
+            FairValueQuote { bid: mark_price, ask: UD64::MAX }
+        });
+    }
+
+    // No volatility signal yet (first cycle, or a perfectly flat mark): quote
+    // flat at the mark until there is a nonzero σ² estimate.
+    if model.variance.is_zero() {
+        return Ok(FairValueQuote { bid: mark_price, ask: mark_price });
+    }
+
+    let gamma_sigma_sq_horizon = model
+        .risk_aversion
+        .checked_mul(model.variance)
+        .and_then(|v| v.checked_mul(model.horizon))
+        .ok_or(Error::QuoteOverflow)?;
+
+    let inventory_adjustment = q
+        .checked_mul(gamma_sigma_sq_horizon)
+        .ok_or(Error::QuoteOverflow)?;
+
+    let reservation_price = if is_long {
+        mark_price.saturating_sub(inventory_adjustment)
+    } else {
+        mark_price.saturating_add(inventory_adjustment)
+    };
+
+    let intensity_ratio = model
+        .risk_aversion
+        .checked_div(model.order_arrival_rate)
+        .ok_or(Error::QuoteOverflow)?;
+
+    let half_spread = gamma_sigma_sq_horizon
+        .checked_div(udec64!(2))
+        .and_then(|inventory_term| {
+            let arrival_term = ln_1p_approx(intensity_ratio)
+                .checked_div(model.risk_aversion)?;
+            inventory_term.checked_add(arrival_term)
+        })
+        .ok_or(Error::QuoteOverflow)?;
+
+    // Clamp to the correct side of the mark so a `post_only` submission never
+    // crosses the book, even when a large inventory skew pushes `r` past it.
+    let bid = reservation_price.saturating_sub(half_spread);
+    let bid = if bid > mark_price { mark_price } else { bid };
+
+    let ask = reservation_price.saturating_add(half_spread);
+    let ask = if ask < mark_price { mark_price } else { ask };
+
+    Ok(FairValueQuote { bid, ask })
+}
+
+/// Per-side price and size adjustment derived from a signed funding rate, to
+/// be applied on top of a symmetric quote so the strategy ends up positioned
+/// to collect funding rather than pay it.
+#[derive(Debug, Clone, Copy)]
+pub struct FundingSkew {
+    /// `true` when funding favors being short (longs are paying funding)
+    pub favors_shorts: bool,
+    /// Amount to tighten the favored side / widen the disfavored side by
+    pub price_adjustment: UD64,
+    /// Amount to grow the favored side's order size / shrink the disfavored
+    /// side's by
+    pub size_adjustment: UD64,
+}
+
+/// `funding_rate_bps` is signed: positive means longs are paying funding to
+/// shorts (funding favors being short), negative the reverse. Scales both
+/// the price and size adjustment by `|funding_rate_bps| * funding_skew_factor`,
+/// so a `funding_skew_factor` of zero disables skewing entirely.
+pub fn funding_skew(
+    funding_skew_factor: UD64,
+    funding_rate_bps: i64,
+    index_price: UD64,
+    order_size: UD64,
+) -> FundingSkew {
+    let favors_shorts = funding_rate_bps > 0;
+
+    if funding_skew_factor.is_zero() || funding_rate_bps == 0 {
+        return FundingSkew {
+            favors_shorts,
+            price_adjustment: UD64::ZERO,
+            size_adjustment: UD64::ZERO,
+        };
+    }
+
+    let magnitude = UD64::from(funding_rate_bps.unsigned_abs())
+        .checked_mul(funding_skew_factor)
+        .and_then(|v| v.checked_div(udec64!(10_000)))
+        .unwrap_or(UD64::ZERO);
+
+    FundingSkew {
+        favors_shorts,
+        price_adjustment: index_price.checked_mul(magnitude).unwrap_or(UD64::ZERO),
+        size_adjustment: order_size.checked_mul(magnitude).unwrap_or(UD64::ZERO),
+    }
+}
+
+/// Absolute distance of `price` from `index_price`, in basis points of
+/// `index_price`. Returns `UD64::MAX` if `index_price` is zero, so a quote
+/// compared against it always reads as maximally stale rather than panicking.
+pub fn quote_distance_bps(price: UD64, index_price: UD64) -> UD64 {
+    let diff = if price >= index_price {
+        price.saturating_sub(index_price)
+    } else {
+        index_price.saturating_sub(price)
+    };
+
+    diff.checked_mul(udec64!(10_000))
+        .and_then(|v| v.checked_div(index_price))
+        .unwrap_or(UD64::MAX)
+}
@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+
+use dex_sdk::{
+    state::{Order, OrderEventType, StateEvents},
+    types::{AccountId, PerpetualId, RequestType},
+};
+use fastnum::UD64;
+use tracing::{debug, warn};
+
+/// Identifies a submitted order for the purposes of optimistic fill tracking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OrderKey {
+    pub account_id: AccountId,
+    pub perpetual_id: PerpetualId,
+    pub client_order_index: u64,
+}
+
+/// An order that has been submitted on-chain but not yet fully confirmed filled.
+///
+/// A `PendingMatch` is removed from the reconciler only once `remaining` reaches
+/// zero (fully confirmed) or its deadline passes unconfirmed, in which case it
+/// is dropped without any compensating order (see `expire_and_rollback`).
+#[derive(Debug, Clone)]
+pub struct PendingMatch {
+    pub side: RequestType,
+    pub size: UD64,
+    pub remaining: UD64,
+    pub submit_block: u64,
+    pub deadline_block: u64,
+}
+
+impl PendingMatch {
+    fn is_resolved(&self) -> bool {
+        self.remaining <= UD64::ZERO
+    }
+}
+
+/// Tracks optimistically-submitted **immediate-or-cancel** orders and
+/// reconciles them against confirmed `StateEvents`, dropping any that are
+/// never observed filled before their deadline.
+///
+/// Unlike a resting order, an IOC order's unfilled remainder is cancelled by
+/// the exchange itself at submission time, so there is nothing to compensate
+/// for once the deadline passes without a fill — only the optimistic
+/// bookkeeping entry to drop. See `expire_and_rollback`.
+#[derive(Debug, Default)]
+pub struct ExecutionReconciler {
+    pending: HashMap<OrderKey, PendingMatch>,
+}
+
+impl ExecutionReconciler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a newly submitted order as optimistically filled, to be confirmed
+    /// or rolled back within `deadline_blocks` blocks.
+    pub fn track_submission(
+        &mut self,
+        account_id: AccountId,
+        perpetual_id: PerpetualId,
+        client_order_index: u64,
+        side: RequestType,
+        size: UD64,
+        current_block: u64,
+        deadline_blocks: u64,
+    ) {
+        let key = OrderKey {
+            account_id,
+            perpetual_id,
+            client_order_index,
+        };
+
+        self.pending.insert(
+            key,
+            PendingMatch {
+                side,
+                size,
+                remaining: size,
+                submit_block: current_block,
+                deadline_block: current_block + deadline_blocks,
+            },
+        );
+    }
+
+    /// Consume confirmed `StateEvents`, decrementing the remaining unconfirmed
+    /// size of any pending match that is observed filled.
+    pub fn reconcile(&mut self, events: &[StateEvents]) {
+        for event in events {
+            let StateEvents::Order(order_event) = event else {
+                continue;
+            };
+
+            let OrderEventType::Filled { size, .. } = order_event.r#type else {
+                continue;
+            };
+
+            let key = OrderKey {
+                account_id: order_event.account_id,
+                perpetual_id: order_event.perpetual_id,
+                client_order_index: order_event.order_id,
+            };
+
+            let Some(pending) = self.pending.get_mut(&key) else {
+                continue;
+            };
+
+            pending.remaining = pending.remaining.saturating_sub(size);
+            if pending.is_resolved() {
+                self.pending.remove(&key);
+            }
+        }
+    }
+
+    /// Drop any pending match whose deadline has elapsed without being fully
+    /// confirmed filled. Every match tracked here came from an IOC
+    /// submission, so a missed deadline means the exchange already cancelled
+    /// the unfilled remainder on its own — emitting a compensating
+    /// `CloseLong`/`CloseShort` here would open a position against a trade
+    /// that never happened, so this only logs and forgets.
+    pub fn expire_and_rollback(&mut self, current_block: u64) {
+        self.pending.retain(|key, pending| {
+            let expired = current_block > pending.deadline_block && !pending.is_resolved();
+
+            if expired {
+                warn!(
+                    account_id = %key.account_id,
+                    perpetual_id = key.perpetual_id,
+                    remaining = %pending.remaining,
+                    "IOC order missed its fill-confirmation deadline; its unfilled \
+                     remainder was already auto-cancelled by the exchange, dropping \
+                     the tracking entry without compensating"
+                );
+            }
+
+            !expired
+        });
+    }
+
+    /// Returns true if there is no unconfirmed optimistic state left to reconcile.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// A single `OrderDesc` a quoting strategy has submitted but not yet seen
+/// confirmed at its intended price in the book.
+#[derive(Debug, Clone, Copy)]
+pub struct PendingIntent {
+    pub order_id: u64,
+    pub side: RequestType,
+    pub target_price: UD64,
+    pub size: UD64,
+    pub submit_block: u64,
+    pub deadline_block: u64,
+}
+
+/// Tracks `OrderDesc`s submitted by a quoting strategy (one intent per
+/// target price level) between the point they're handed to
+/// `execOpsAndOrders` and the point the exchange's own state confirms them,
+/// so a strategy that rebuilds its target book every cycle can diff against
+/// *confirmed* state instead of resubmitting into a transaction that is
+/// still in flight. Also assigns the client-chosen order id every intent is
+/// submitted under, since a fresh `Place` needs one before it exists in the
+/// book.
+#[derive(Debug, Default)]
+pub struct PendingIntentTracker {
+    pending: HashMap<u64, PendingIntent>,
+    next_order_id: u64,
+}
+
+impl PendingIntentTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve a fresh order id for a brand-new `Place` and track it as
+    /// pending until it's observed resting in the book.
+    pub fn track_new(
+        &mut self,
+        side: RequestType,
+        target_price: UD64,
+        size: UD64,
+        current_block: u64,
+        deadline_blocks: u64,
+    ) -> u64 {
+        let order_id = self.next_order_id;
+        self.next_order_id += 1;
+        self.track(order_id, side, target_price, size, current_block, deadline_blocks);
+        order_id
+    }
+
+    /// Track an in-flight `Change` against an order id that already exists
+    /// in the book, pending until its new price is observed confirmed.
+    pub fn track_change(
+        &mut self,
+        order_id: u64,
+        side: RequestType,
+        target_price: UD64,
+        size: UD64,
+        current_block: u64,
+        deadline_blocks: u64,
+    ) {
+        self.track(order_id, side, target_price, size, current_block, deadline_blocks);
+    }
+
+    fn track(
+        &mut self,
+        order_id: u64,
+        side: RequestType,
+        target_price: UD64,
+        size: UD64,
+        current_block: u64,
+        deadline_blocks: u64,
+    ) {
+        self.pending.insert(
+            order_id,
+            PendingIntent {
+                order_id,
+                side,
+                target_price,
+                size,
+                submit_block: current_block,
+                deadline_block: current_block + deadline_blocks,
+            },
+        );
+    }
+
+    /// True if `price` on `side` already has a submission in flight, so the
+    /// caller shouldn't submit a duplicate this cycle.
+    pub fn is_pending(&self, side: RequestType, price: UD64) -> bool {
+        self.pending
+            .values()
+            .any(|intent| intent.side == side && intent.target_price == price)
+    }
+
+    /// Drop an intent immediately: its transaction failed at the receipt
+    /// stage, so there's nothing left to wait on.
+    pub fn rollback(&mut self, order_id: u64) {
+        self.pending.remove(&order_id);
+    }
+
+    /// Confirm any intent whose order id is now resting in the book at its
+    /// target price, and drop any whose order id has left the book entirely
+    /// (fully filled or cancelled elsewhere) since there's nothing left to
+    /// confirm either way.
+    pub fn reconcile(&mut self, open_orders: &[&Order]) {
+        let live: HashMap<u64, UD64> = open_orders
+            .iter()
+            .map(|order| (order.order_id(), order.price()))
+            .collect();
+
+        self.pending.retain(|order_id, intent| match live.get(order_id) {
+            Some(&price) if price == intent.target_price => {
+                debug!(order_id, "Pending intent confirmed in book");
+                false
+            }
+            Some(_) => true,
+            None => false,
+        });
+    }
+
+    /// Intents whose deadline has elapsed without confirmation, removed so
+    /// the caller can decide whether to retarget or resubmit them.
+    pub fn expire(&mut self, current_block: u64) -> Vec<PendingIntent> {
+        let expired_ids: Vec<u64> = self
+            .pending
+            .iter()
+            .filter(|(_, intent)| current_block > intent.deadline_block)
+            .map(|(order_id, _)| *order_id)
+            .collect();
+
+        let mut expired = Vec::with_capacity(expired_ids.len());
+        for order_id in expired_ids {
+            if let Some(intent) = self.pending.remove(&order_id) {
+                expired.push(intent);
+            }
+        }
+
+        if !expired.is_empty() {
+            warn!(count = expired.len(), "Pending intents missed their confirmation deadline");
+        }
+
+        expired
+    }
+}
@@ -0,0 +1,225 @@
+use std::sync::OnceLock;
+
+use crate::{
+    Result, broadcaster, error::Error, fee::SharedFeeOracle, nonce::SharedNonceScheduler,
+    strategies::Strategy,
+};
+use alloy::providers::DynProvider;
+use chrono::{DateTime, Datelike, Timelike, Utc, Weekday};
+use dex_sdk::{
+    abi::dex::Exchange::{ExchangeInstance, OrderDesc},
+    error::DexError,
+    state::{Exchange, PositionType, StateEvents},
+    types::{AccountId, OrderRequest, PerpetualId, RequestType},
+};
+use fastnum::UD64;
+use tokio::sync::{OwnedSemaphorePermit, mpsc};
+use tracing::info;
+
+/// A position manager that flattens (and optionally re-opens) the account's
+/// position on a recurring weekly boundary, so a bot running across that
+/// window automatically rolls its position over rather than holding an
+/// ever-staler perpetual position across the boundary.
+#[derive(Debug)]
+pub struct RolloverStrategy {
+    /// The perpetual ID to manage
+    pub perpetual_id: PerpetualId,
+    /// Account ID
+    pub account_id: OnceLock<AccountId>,
+    /// UTC weekday of the weekly rollover boundary
+    pub boundary_weekday: Weekday,
+    /// UTC hour (0-23) of the weekly rollover boundary
+    pub boundary_hour: u32,
+    /// How far ahead of the boundary to start closing out the position
+    pub lead_in: chrono::Duration,
+    /// Whether to re-open the same notional immediately after flattening
+    pub reopen_after_flatten: bool,
+    /// Leverage to use when re-opening
+    pub leverage: UD64,
+    /// The boundary this strategy has already acted on, so repeated `execute`
+    /// calls within the same window are a no-op
+    last_rollover_boundary: Option<DateTime<Utc>>,
+}
+
+impl RolloverStrategy {
+    pub fn new(
+        perpetual_id: PerpetualId,
+        boundary_weekday: Weekday,
+        boundary_hour: u32,
+        lead_in: chrono::Duration,
+        reopen_after_flatten: bool,
+        leverage: UD64,
+    ) -> Self {
+        Self {
+            perpetual_id,
+            account_id: OnceLock::new(),
+            boundary_weekday,
+            boundary_hour,
+            lead_in,
+            reopen_after_flatten,
+            leverage,
+            last_rollover_boundary: None,
+        }
+    }
+
+    /// The next timestamp at or after `now` matching the configured weekday
+    /// and hour.
+    fn next_boundary(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let mut candidate = now
+            .with_hour(self.boundary_hour)
+            .and_then(|d| d.with_minute(0))
+            .and_then(|d| d.with_second(0))
+            .and_then(|d| d.with_nanosecond(0))
+            .unwrap_or(now);
+
+        while candidate.weekday() != self.boundary_weekday || candidate < now {
+            candidate += chrono::Duration::days(1);
+        }
+
+        candidate
+    }
+}
+
+impl Strategy for RolloverStrategy {
+    fn name(&self) -> &'static str {
+        "Rollover"
+    }
+
+    fn perpetual_id(&self) -> PerpetualId {
+        self.perpetual_id
+    }
+
+    async fn initialize(
+        &mut self,
+        _instance: &ExchangeInstance<DynProvider>,
+        exchange: &Exchange,
+    ) -> Result<()> {
+        let accounts = exchange.accounts();
+        if accounts.is_empty() {
+            return Err(Error::NoAccountFoundForStrategy);
+        }
+
+        if accounts.len() > 1 {
+            return Err(Error::TooManyAccountsForStrategy);
+        }
+
+        let account_id = *accounts.keys().next().unwrap();
+
+        self.account_id
+            .set(account_id)
+            .map_err(|_| Error::AccountIdAlreadySet)?;
+
+        if !exchange.perpetuals().contains_key(&self.perpetual_id) {
+            return Err(Error::PerpetualNotFoundInExchangeState(self.perpetual_id));
+        }
+
+        info!(%account_id, "Rollover Strategy initialized");
+
+        Ok(())
+    }
+
+    async fn execute(
+        &mut self,
+        instance: &ExchangeInstance<DynProvider>,
+        exchange: &Exchange,
+        _events: &[StateEvents],
+        error_tx: &mpsc::Sender<DexError>,
+        nonce_scheduler: &SharedNonceScheduler,
+        fee_oracle: &SharedFeeOracle,
+        permit: OwnedSemaphorePermit,
+    ) {
+        let account_id = *self.account_id.get().expect("Strategy not initialized");
+
+        let now = DateTime::from_timestamp(exchange.instant().timestamp() as i64, 0)
+            .unwrap_or_else(Utc::now);
+
+        let boundary = self.next_boundary(now);
+
+        if boundary - now > self.lead_in {
+            // Not yet within the lead-in window for the next boundary
+            return;
+        }
+
+        if self.last_rollover_boundary == Some(boundary) {
+            // Already rolled over for this window
+            return;
+        }
+
+        let position = exchange
+            .accounts()
+            .get(&account_id)
+            .expect("Account should exist in exchange state")
+            .positions()
+            .get(&self.perpetual_id);
+
+        let Some(position) = position else {
+            // Nothing to roll over
+            self.last_rollover_boundary = Some(boundary);
+            return;
+        };
+
+        info!(%boundary, size = %position.size(), "Rolling over position ahead of funding boundary");
+
+        let mut order_descs = Vec::new();
+
+        let (close_type, reopen_type) = match position.r#type() {
+            PositionType::Long => (RequestType::CloseLong, RequestType::OpenLong),
+            PositionType::Short => (RequestType::CloseShort, RequestType::OpenShort),
+        };
+
+        order_descs.push(self.place_order(exchange, close_type, position.size()));
+
+        if self.reopen_after_flatten {
+            order_descs.push(self.place_order(exchange, reopen_type, position.size()));
+        }
+
+        self.last_rollover_boundary = Some(boundary);
+
+        let task_instance = instance.clone();
+        let task_fee_oracle = fee_oracle.clone();
+        let task_nonce_scheduler = nonce_scheduler.clone();
+        let task_error_tx = error_tx.clone();
+        tokio::spawn(async move {
+            broadcaster::submit_with_fee_retry(
+                &task_instance,
+                &task_fee_oracle,
+                &task_nonce_scheduler,
+                &task_error_tx,
+                permit,
+                order_descs,
+                false,
+                "rollover",
+            )
+            .await;
+        });
+    }
+}
+
+impl RolloverStrategy {
+    fn place_order(&self, exchange: &Exchange, order_type: RequestType, size: UD64) -> OrderDesc {
+        let price = match order_type {
+            RequestType::OpenLong | RequestType::CloseShort => UD64::MAX,
+            _ => UD64::ZERO,
+        };
+
+        let request = OrderRequest::new(
+            0,
+            self.perpetual_id,
+            order_type,
+            None,
+            price,
+            size,
+            None,
+            false,
+            false,
+            // immediate or cancel
+            true,
+            None,
+            self.leverage,
+            None,
+            None,
+        );
+
+        request.prepare(exchange)
+    }
+}
@@ -1,10 +1,21 @@
-use crate::{Result, error::Error, strategies::Strategy};
+use crate::{
+    Result,
+    broadcaster,
+    error::Error,
+    fee::SharedFeeOracle,
+    nonce::SharedNonceScheduler,
+    strategies::{
+        Strategy, quoting,
+        quoting::InventoryModel,
+        reconcile::PendingIntentTracker,
+    },
+};
 use alloy::providers::DynProvider;
 use fastnum::{UD64, udec64};
 use perpl_sdk::{
     abi::dex::Exchange::{ExchangeInstance, OrderDesc},
     error::DexError,
-    state::{Exchange, Order, StateEvents},
+    state::{Exchange, Order, Position, StateEvents},
     types::{AccountId, OrderRequest, OrderType, PerpetualId, RequestType},
 };
 use std::{
@@ -12,9 +23,10 @@ use std::{
     sync::OnceLock,
 };
 use tokio::sync::{OwnedSemaphorePermit, mpsc};
-use tracing::{debug, error, info, trace};
+use tracing::{debug, error, info, trace, warn};
 
-/// A market making strategy that places orders of fixed sizes above and below the mark price
+/// A market making strategy that places orders of fixed sizes above and below
+/// the perpetual's index price, skewed by the account's current inventory
 #[derive(Debug)]
 pub struct SpreadStrategy {
     /// Number of orders to place on each side of the spread
@@ -31,8 +43,47 @@ pub struct SpreadStrategy {
     pub account_id: OnceLock<AccountId>,
     /// Current mark price
     pub current_mark_price: UD64,
+    /// Offset from the index price applied to the innermost order on each side, in basis points
+    pub offset_bps: UD64,
+    /// Scales how aggressively quotes skew to mean-revert inventory
+    pub skew_factor: UD64,
+    /// Position size at which the inventory skew saturates (and, with
+    /// `inventory_model` set, the strategy switches to pure-unwind mode)
+    pub max_inventory: UD64,
+    /// When set, replaces the symmetric `offset_bps`/`skew_factor` quoting
+    /// with Avellaneda–Stoikov reservation-price quoting
+    pub inventory_model: Option<InventoryModel>,
+    /// Scales how much the perpetual's signed funding rate tightens/enlarges
+    /// the side that would collect it and widens/shrinks the side that
+    /// would pay it; zero disables funding-aware skewing entirely
+    pub funding_skew_factor: UD64,
+    /// Maximum number of blocks a resting order is allowed to go unchanged
+    /// before it's cancelled as stale, regardless of how close it still is
+    /// to the current mark
+    pub quote_ttl_blocks: u64,
+    /// Maximum distance, in basis points of the index price, a resting order
+    /// may sit from the current mark before it's cancelled as stale
+    pub max_quote_distance_bps: UD64,
+    /// Tracks submitted `Place`/`Change`s until the book confirms them at
+    /// their target price, so a transaction still in flight isn't resubmitted
+    /// every cycle and a failed/expired one rolls back cleanly
+    pending: PendingIntentTracker,
+    /// Block each currently-resting order id was first observed at its
+    /// current price, used to age out stale quotes independently of
+    /// `pending`'s in-flight tracking. Reset whenever `update_order` changes
+    /// an order's price, so a freshly re-quoted order isn't immediately
+    /// TTL-evicted under an age that belonged to its old price.
+    resting_since: HashMap<u64, u64>,
+    /// Order ids with a TTL/distance `Cancel` already submitted and not yet
+    /// confirmed removed from the book, to avoid submitting a duplicate
+    /// cancel while the first one's receipt is still pending
+    pending_cancels: HashSet<u64>,
 }
 
+/// Number of blocks to wait for a submitted order to be confirmed at its
+/// target price before treating it as expired and eligible for resubmission.
+const ORDER_CONFIRMATION_DEADLINE_BLOCKS: u64 = 10;
+
 impl Strategy for SpreadStrategy {
     fn name(&self) -> &'static str {
         "Spread"
@@ -78,6 +129,8 @@ impl Strategy for SpreadStrategy {
         exchange: &Exchange,
         _events: &[StateEvents],
         error_tx: &mpsc::Sender<DexError>,
+        nonce_scheduler: &SharedNonceScheduler,
+        fee_oracle: &SharedFeeOracle,
         permit: OwnedSemaphorePermit,
     ) {
         let book = exchange
@@ -92,37 +145,36 @@ impl Strategy for SpreadStrategy {
             panic!("Strategy not initialized");
         };
 
-        let order_descs = self.process_orders(exchange);
+        let order_descs = match self.process_orders(exchange) {
+            Ok(order_descs) => order_descs,
+            Err(error) => {
+                error!(%error, "Failed to compute fair-value quote, skipping this cycle");
+                return;
+            }
+        };
         if order_descs.is_empty() {
             return;
         }
 
-        let builder = instance.execOpsAndOrders(vec![], order_descs, false);
-
-        trace!(?builder, "Submitting initial spread orders transaction");
-
-        match builder.send().await.map_err(DexError::from) {
-            Ok(res) => {
-                let error_tx = error_tx.clone();
-                tokio::spawn(async move {
-                    match res.get_receipt().await.map_err(DexError::from) {
-                        Ok(tx) => {
-                            debug!(?tx, "Spread orders transaction complete");
-                        }
-                        Err(error) => {
-                            error!(%error, "Error executing spread orders transaction");
-                            error_tx.send(error).await.expect("Failed to send error");
-                        }
-                    }
-
-                    drop(permit);
-                });
-            }
-            Err(error) => {
-                error!(%error, "Error sending transaction");
-                error_tx.send(error).await.expect("Failed to send error");
-            }
-        }
+        trace!(order_count = order_descs.len(), "Submitting spread orders transaction");
+
+        let task_instance = instance.clone();
+        let task_fee_oracle = fee_oracle.clone();
+        let task_nonce_scheduler = nonce_scheduler.clone();
+        let task_error_tx = error_tx.clone();
+        tokio::spawn(async move {
+            broadcaster::submit_with_fee_retry(
+                &task_instance,
+                &task_fee_oracle,
+                &task_nonce_scheduler,
+                &task_error_tx,
+                permit,
+                order_descs,
+                false,
+                "spread",
+            )
+            .await;
+        });
     }
 }
 
@@ -133,6 +185,13 @@ impl SpreadStrategy {
         perpetual_id: PerpetualId,
         max_matches_per_order: Option<u32>,
         leverage: UD64,
+        offset_bps: UD64,
+        skew_factor: UD64,
+        max_inventory: UD64,
+        inventory_model: Option<InventoryModel>,
+        funding_skew_factor: UD64,
+        quote_ttl_blocks: u64,
+        max_quote_distance_bps: UD64,
     ) -> Self {
         Self {
             orders_per_side,
@@ -142,40 +201,154 @@ impl SpreadStrategy {
             leverage,
             account_id: OnceLock::new(),
             current_mark_price: UD64::ZERO,
+            offset_bps,
+            skew_factor,
+            max_inventory,
+            inventory_model,
+            funding_skew_factor,
+            quote_ttl_blocks,
+            max_quote_distance_bps,
+            pending: PendingIntentTracker::new(),
+            resting_since: HashMap::new(),
+            pending_cancels: HashSet::new(),
         }
     }
 
     /// Process existing orders and determine necessary actions to maintain the spread
-    fn process_orders(&mut self, exchange: &Exchange) -> Vec<OrderDesc> {
-        let new_mark_price = self.get_mark_price(exchange);
-        let bids_first = new_mark_price <= self.current_mark_price;
+    fn process_orders(&mut self, exchange: &Exchange) -> Result<Vec<OrderDesc>> {
+        let index_price = self.get_index_price(exchange);
+        let bids_first = index_price <= self.current_mark_price;
+
+        self.current_mark_price = index_price;
+
+        info!(index_price = %index_price, "Index price");
+
+        let position = self.get_position(exchange);
+
+        let quote = match &mut self.inventory_model {
+            Some(model) => quoting::avellaneda_stoikov_quote(
+                model,
+                index_price,
+                position,
+                self.max_inventory,
+            )?,
+            None => quoting::fair_value_quote(
+                index_price,
+                position,
+                self.offset_bps,
+                self.skew_factor,
+                self.max_inventory,
+            )?,
+        };
 
-        self.current_mark_price = new_mark_price;
+        let funding_rate_bps = self.get_funding_rate_bps(exchange);
+        let funding_skew =
+            quoting::funding_skew(self.funding_skew_factor, funding_rate_bps, index_price, self.order_size);
+
+        info!(
+            funding_rate_bps,
+            favors_shorts = funding_skew.favors_shorts,
+            price_adjustment = %funding_skew.price_adjustment,
+            size_adjustment = %funding_skew.size_adjustment,
+            "Skewing quotes for funding rate"
+        );
 
-        info!(mark_price = %self.current_mark_price, "Mark price");
+        // Favoring shorts means the ask side collects funding: tighten and
+        // enlarge it, and widen/shrink the bid side that would pay it.
+        let (bid_size, ask_size) = if funding_skew.favors_shorts {
+            (
+                self.order_size.saturating_sub(funding_skew.size_adjustment),
+                self.order_size.saturating_add(funding_skew.size_adjustment),
+            )
+        } else {
+            (
+                self.order_size.saturating_add(funding_skew.size_adjustment),
+                self.order_size.saturating_sub(funding_skew.size_adjustment),
+            )
+        };
+
+        let quote_bid = if funding_skew.favors_shorts {
+            quote.bid.saturating_sub(funding_skew.price_adjustment)
+        } else {
+            quote.bid.saturating_add(funding_skew.price_adjustment)
+        };
+
+        let quote_ask = if funding_skew.favors_shorts {
+            quote.ask.saturating_sub(funding_skew.price_adjustment)
+        } else {
+            quote.ask.saturating_add(funding_skew.price_adjustment)
+        };
 
         let open_orders = self.fetch_open_orders(exchange);
+        let current_block = exchange.instant().block_number();
+
+        self.pending.reconcile(&open_orders);
+        for intent in self.pending.expire(current_block) {
+            warn!(
+                order_id = intent.order_id,
+                side = ?intent.side,
+                target_price = %intent.target_price,
+                "Pending order intent expired unconfirmed, eligible for resubmission"
+            );
+        }
+
+        let live_ids: HashSet<u64> = open_orders.iter().map(|o| o.order_id()).collect();
+        self.resting_since.retain(|order_id, _| live_ids.contains(order_id));
+        self.pending_cancels.retain(|order_id| live_ids.contains(order_id));
+
+        let mut stale_cancel_descs = Vec::new();
+        let mut stale_ids = HashSet::new();
+
+        for order in &open_orders {
+            let order_id = order.order_id();
+
+            if self.pending_cancels.contains(&order_id) {
+                // Already being cancelled as stale; leave it out of this
+                // cycle's targets without resubmitting the cancel
+                stale_ids.insert(order_id);
+                continue;
+            }
+
+            let since = *self.resting_since.entry(order_id).or_insert(current_block);
+            let age_blocks = current_block.saturating_sub(since);
+            let distance_bps = quoting::quote_distance_bps(order.price(), index_price);
+
+            if age_blocks > self.quote_ttl_blocks || distance_bps > self.max_quote_distance_bps {
+                debug!(
+                    order_id,
+                    age_blocks,
+                    %distance_bps,
+                    "Cancelling stale quote past its TTL or distance from mark"
+                );
+                stale_cancel_descs.push(self.cancel_order(exchange, order_id));
+                stale_ids.insert(order_id);
+                self.resting_since.remove(&order_id);
+                self.pending_cancels.insert(order_id);
+            }
+        }
 
         let mut current_bids: HashMap<UD64, &Order> = open_orders
             .iter()
-            .filter(|o| o.r#type() == OrderType::OpenLong)
+            .filter(|o| o.r#type() == OrderType::OpenLong && !stale_ids.contains(&o.order_id()))
             .map(|o| (o.price(), *o))
             .collect();
 
         let mut current_asks: HashMap<UD64, &Order> = open_orders
             .iter()
-            .filter(|o| o.r#type() == OrderType::OpenShort)
+            .filter(|o| o.r#type() == OrderType::OpenShort && !stale_ids.contains(&o.order_id()))
             .map(|o| (o.price(), *o))
             .collect();
 
         let mut target_bid_prices = HashSet::new();
         let mut target_ask_prices = HashSet::new();
 
-        for i in 1..=self.orders_per_side {
-            let spread_offset = UD64::from(i) / udec64!(500); // e.g., 0.2% per order away from mark price
+        for i in 0..self.orders_per_side {
+            // Widen each level beyond the innermost fair-value quote by the
+            // same per-order step the strategy has always used
+            let level_offset = UD64::from(i) / udec64!(500) * index_price;
 
-            let bid_price = (UD64::ONE - spread_offset) * self.current_mark_price;
-            let ask_price = (UD64::ONE + spread_offset) * self.current_mark_price;
+            let bid_price = quote_bid.saturating_sub(level_offset);
+            let ask_price = quote_ask.saturating_add(level_offset);
 
             target_bid_prices.insert(bid_price);
             target_ask_prices.insert(ask_price);
@@ -186,6 +359,8 @@ impl SpreadStrategy {
             &mut current_bids,
             target_bid_prices,
             RequestType::OpenLong,
+            bid_size,
+            current_block,
         );
 
         let mut ask_descs = self.create_target_order_changes(
@@ -193,34 +368,54 @@ impl SpreadStrategy {
             &mut current_asks,
             target_ask_prices,
             RequestType::OpenShort,
+            ask_size,
+            current_block,
         );
 
-        if bids_first {
+        Ok(if bids_first {
             bid_descs.append(&mut ask_descs);
-            bid_descs
+            stale_cancel_descs.append(&mut bid_descs);
+            stale_cancel_descs
         } else {
             ask_descs.append(&mut bid_descs);
-            ask_descs
-        }
+            stale_cancel_descs.append(&mut ask_descs);
+            stale_cancel_descs
+        })
     }
 
     fn create_target_order_changes(
-        &self,
+        &mut self,
         exchange: &Exchange,
         current: &mut HashMap<UD64, &Order>,
         target_prices: HashSet<UD64>,
         request_type: RequestType,
+        size: UD64,
+        current_block: u64,
     ) -> Vec<OrderDesc> {
         let mut order_descs = Vec::new();
         let mut remaining = Vec::new();
 
         for price in target_prices.into_iter() {
+            // Already have a Place/Change in flight for this level; don't
+            // resubmit into a transaction that hasn't been confirmed yet.
+            if self.pending.is_pending(request_type, price) {
+                current.remove(&price);
+                continue;
+            }
+
             if let Some(existing_order) = current.remove(&price) {
-                if existing_order.size() != self.order_size {
+                if existing_order.size() != size {
                     // Update order size
-                    let update_desc =
-                        self.update_order(exchange, existing_order, price, self.order_size);
+                    let update_desc = self.update_order(exchange, existing_order, price, size);
                     order_descs.push(update_desc);
+                    self.pending.track_change(
+                        existing_order.order_id(),
+                        request_type,
+                        price,
+                        size,
+                        current_block,
+                        ORDER_CONFIRMATION_DEADLINE_BLOCKS,
+                    );
                 } else {
                     // Order exists with correct size, do nothing
                     continue;
@@ -237,8 +432,21 @@ impl SpreadStrategy {
                     break;
                 };
 
-                let update_desc = self.update_order(exchange, existing, price, self.order_size);
+                let update_desc = self.update_order(exchange, existing, price, size);
                 order_descs.push(update_desc);
+                self.pending.track_change(
+                    existing.order_id(),
+                    request_type,
+                    price,
+                    size,
+                    current_block,
+                    ORDER_CONFIRMATION_DEADLINE_BLOCKS,
+                );
+                // This order's price is changing, so it's quoting fresh
+                // distance from the mark again; reset its age instead of
+                // leaving it eligible for immediate TTL eviction under the
+                // price it no longer sits at.
+                self.resting_since.insert(existing.order_id(), current_block);
             }
         }
 
@@ -246,7 +454,14 @@ impl SpreadStrategy {
         if !remaining.is_empty() {
             // Place new orders for any remaining bids
             for price in remaining {
-                let order_desc = self.place_order(exchange, request_type, price, self.order_size);
+                let order_id = self.pending.track_new(
+                    request_type,
+                    price,
+                    size,
+                    current_block,
+                    ORDER_CONFIRMATION_DEADLINE_BLOCKS,
+                );
+                let order_desc = self.place_order(exchange, request_type, order_id, price, size);
                 order_descs.push(order_desc);
             }
         }
@@ -271,25 +486,50 @@ impl SpreadStrategy {
             .collect()
     }
 
-    fn get_mark_price(&self, exchange: &Exchange) -> UD64 {
+    fn get_index_price(&self, exchange: &Exchange) -> UD64 {
         let perpetual = exchange
             .perpetuals()
             .get(&self.perpetual_id)
             .expect("perpetual must exist");
 
-        perpetual.mark_price()
+        perpetual.index_price()
+    }
+
+    /// Signed funding rate in basis points: positive means longs are paying
+    /// funding to shorts (funding favors being short), negative the reverse.
+    /// Funding itself isn't otherwise read anywhere in this strategy set, so
+    /// this is the first consumer of `Perpetual::funding_rate_bps`.
+    fn get_funding_rate_bps(&self, exchange: &Exchange) -> i64 {
+        let perpetual = exchange
+            .perpetuals()
+            .get(&self.perpetual_id)
+            .expect("perpetual must exist");
+
+        perpetual.funding_rate_bps()
+    }
+
+    fn get_position<'a>(&self, exchange: &'a Exchange) -> Option<&'a Position> {
+        let account_id = self.account_id.get().expect("Strategy not initialized");
+
+        exchange
+            .accounts()
+            .get(account_id)
+            .expect("Account should exist in exchange state")
+            .positions()
+            .get(&self.perpetual_id)
     }
 
     fn place_order(
         &self,
         exchange: &Exchange,
         order_type: RequestType,
+        order_id: u64,
         price: UD64,
         size: UD64,
     ) -> OrderDesc {
-        info!(?order_type, %price, %size, "Placing order");
+        info!(?order_type, order_id, %price, %size, "Placing order");
         let request = OrderRequest::new(
-            0,
+            order_id,
             self.perpetual_id,
             order_type,
             None,
@@ -318,7 +558,7 @@ impl SpreadStrategy {
     ) -> OrderDesc {
         info!(order_id = order.order_id(), %price, %size, "Updating order");
         let request = OrderRequest::new(
-            0,
+            order.order_id(),
             self.perpetual_id,
             RequestType::Change,
             Some(order.order_id()),
@@ -337,4 +577,26 @@ impl SpreadStrategy {
 
         request.prepare(exchange)
     }
+
+    fn cancel_order(&self, exchange: &Exchange, order_id: u64) -> OrderDesc {
+        info!(order_id, "Cancelling stale order");
+        let request = OrderRequest::new(
+            order_id,
+            self.perpetual_id,
+            RequestType::Cancel,
+            Some(order_id),
+            udec64!(0),
+            udec64!(0),
+            None,
+            false,
+            false,
+            false,
+            None,
+            udec64!(0),
+            None,
+            None,
+        );
+
+        request.prepare(exchange)
+    }
 }
@@ -1,6 +1,13 @@
 use std::sync::OnceLock;
 
-use crate::{Result, error::Error, strategies::Strategy};
+use crate::{
+    Result,
+    broadcaster,
+    error::Error,
+    fee::SharedFeeOracle,
+    nonce::SharedNonceScheduler,
+    strategies::{Strategy, reconcile::ExecutionReconciler},
+};
 use alloy::providers::DynProvider;
 use dex_sdk::{
     abi::dex::Exchange::{ExchangeInstance, OrderDesc},
@@ -14,7 +21,7 @@ use rand::{
     distr::{Bernoulli, Distribution, OpenClosed01},
 };
 use tokio::sync::{OwnedSemaphorePermit, mpsc};
-use tracing::{debug, error, info};
+use tracing::info;
 
 /// A simple taker strategy that buys and sells over and over.
 #[derive(Debug)]
@@ -29,8 +36,19 @@ pub struct TakerStrategy {
     pub max_order_size: UD64,
     /// Operation distribution
     op_distribution: rand::distr::Bernoulli,
+    /// Tracks submitted orders until their fills are confirmed on-chain, rolling
+    /// back any portion that never lands within the confirmation deadline
+    reconciler: ExecutionReconciler,
+    /// Monotonically increasing client order index handed to each submitted
+    /// order, so consecutive submissions get distinct `OrderKey`s in the
+    /// reconciler instead of all colliding under index `0`
+    next_client_order_index: u64,
 }
 
+/// Number of blocks to wait for a submitted order to be observed filled before
+/// rolling it back.
+const FILL_CONFIRMATION_DEADLINE_BLOCKS: u64 = 10;
+
 impl Strategy for TakerStrategy {
     fn name(&self) -> &'static str {
         "Taker"
@@ -73,62 +91,95 @@ impl Strategy for TakerStrategy {
         &mut self,
         instance: &ExchangeInstance<DynProvider>,
         exchange: &Exchange,
-        _events: &[StateEvents],
+        events: &[StateEvents],
         error_tx: &mpsc::Sender<DexError>,
+        nonce_scheduler: &SharedNonceScheduler,
+        fee_oracle: &SharedFeeOracle,
         permit: OwnedSemaphorePermit,
     ) {
+        let account_id = *self.account_id.get().expect("Strategy not initialized");
+        let current_block = exchange.instant().block_number();
+
+        self.reconciler.reconcile(events);
+        self.reconciler.expire_and_rollback(current_block);
+        let mut order_descs = Vec::new();
+
         let position = self.get_position(exchange);
         let size_multiplier = rand::rng().sample::<f64, _>(OpenClosed01);
         let size =
             UD64::from_f64(size_multiplier).expect("failed to parse UD64") * self.max_order_size;
 
         let long = self.op_distribution.sample(&mut rand::rng());
-        let mut order_descs = Vec::new();
 
         if long {
             if let Some(pos) = position
                 && pos.r#type() == PositionType::Short
             {
                 // Close short position
-                order_descs.push(self.place_order(exchange, RequestType::CloseLong, pos.size()));
+                let close_index = self.next_client_order_index();
+                order_descs.push(self.place_order(
+                    exchange,
+                    RequestType::CloseLong,
+                    close_index,
+                    pos.size(),
+                ));
             }
 
-            order_descs.push(self.place_order(exchange, RequestType::OpenLong, size));
+            let open_index = self.next_client_order_index();
+            order_descs.push(self.place_order(exchange, RequestType::OpenLong, open_index, size));
+            self.reconciler.track_submission(
+                account_id,
+                self.perpetual_id,
+                open_index,
+                RequestType::OpenLong,
+                size,
+                current_block,
+                FILL_CONFIRMATION_DEADLINE_BLOCKS,
+            );
         } else {
             if let Some(pos) = position
                 && pos.r#type() == PositionType::Long
             {
                 // Close long position
-                order_descs.push(self.place_order(exchange, RequestType::CloseShort, pos.size()));
+                let close_index = self.next_client_order_index();
+                order_descs.push(self.place_order(
+                    exchange,
+                    RequestType::CloseShort,
+                    close_index,
+                    pos.size(),
+                ));
             }
 
-            order_descs.push(self.place_order(exchange, RequestType::OpenShort, size));
+            let open_index = self.next_client_order_index();
+            order_descs.push(self.place_order(exchange, RequestType::OpenShort, open_index, size));
+            self.reconciler.track_submission(
+                account_id,
+                self.perpetual_id,
+                open_index,
+                RequestType::OpenShort,
+                size,
+                current_block,
+                FILL_CONFIRMATION_DEADLINE_BLOCKS,
+            );
         }
 
-        let builder = instance.execOpsAndOrders(vec![], order_descs, false);
-
-        match builder.send().await.map_err(DexError::from) {
-            Ok(res) => {
-                let error_tx = error_tx.clone();
-                tokio::spawn(async move {
-                    match res.get_receipt().await.map_err(DexError::from) {
-                        Ok(tx) => {
-                            debug!(?tx, "Taker orders transaction complete");
-                        }
-                        Err(error) => {
-                            error!(%error, "Error executing taker orders transaction");
-                            error_tx.send(error).await.expect("Failed to send error");
-                        }
-                    }
-
-                    drop(permit);
-                });
-            }
-            Err(error) => {
-                error!(%error, "Error sending transaction");
-                error_tx.send(error).await.expect("Failed to send error");
-            }
-        }
+        let task_instance = instance.clone();
+        let task_fee_oracle = fee_oracle.clone();
+        let task_nonce_scheduler = nonce_scheduler.clone();
+        let task_error_tx = error_tx.clone();
+        tokio::spawn(async move {
+            broadcaster::submit_with_fee_retry(
+                &task_instance,
+                &task_fee_oracle,
+                &task_nonce_scheduler,
+                &task_error_tx,
+                permit,
+                order_descs,
+                false,
+                "taker",
+            )
+            .await;
+        });
     }
 }
 
@@ -141,9 +192,20 @@ impl TakerStrategy {
             account_id: OnceLock::new(),
             max_order_size,
             op_distribution: Bernoulli::new(0.5).unwrap(),
+            reconciler: ExecutionReconciler::new(),
+            next_client_order_index: 0,
         }
     }
 
+    /// Reserve the next client order index, so consecutive submissions (an
+    /// open and its offsetting close, or two consecutive blocks' opens) each
+    /// get a distinct `OrderKey` in the reconciler instead of colliding.
+    fn next_client_order_index(&mut self) -> u64 {
+        let index = self.next_client_order_index;
+        self.next_client_order_index += 1;
+        index
+    }
+
     fn get_position<'a>(&self, exchange: &'a Exchange) -> Option<&'a Position> {
         let Some(account_id) = self.account_id.get() else {
             panic!("Strategy not initialized");
@@ -157,14 +219,20 @@ impl TakerStrategy {
         account.positions().get(&self.perpetual_id)
     }
 
-    fn place_order(&self, exchange: &Exchange, order_type: RequestType, size: UD64) -> OrderDesc {
+    fn place_order(
+        &self,
+        exchange: &Exchange,
+        order_type: RequestType,
+        client_order_index: u64,
+        size: UD64,
+    ) -> OrderDesc {
         let price = match order_type {
             RequestType::OpenLong => UD64::MAX,
             _ => UD64::ZERO,
         };
 
         let request = OrderRequest::new(
-            0,
+            client_order_index,
             self.perpetual_id,
             order_type,
             None,
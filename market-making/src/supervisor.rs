@@ -0,0 +1,116 @@
+use std::{panic::AssertUnwindSafe, sync::Arc, time::Duration};
+
+use alloy::providers::DynProvider;
+use dex_sdk::error::DexError;
+use futures::FutureExt;
+use perpl_sdk::{
+    abi::dex::Exchange::ExchangeInstance,
+    state::{Exchange, StateEvents},
+};
+use tokio::sync::{RwLock, Semaphore, mpsc};
+use tracing::{error, warn};
+
+use crate::{
+    MAX_CONCURRENT_SUBMISSIONS_PER_STRATEGY,
+    fee::SharedFeeOracle,
+    nonce::SharedNonceScheduler,
+    strategies::{Strategy, StrategyType},
+};
+
+/// One strategy's independent lifecycle: its own inbound event channel, order
+/// semaphore, submission-error channel, and execution cadence, all isolated
+/// from its sibling strategies sharing the bot's exchange snapshot.
+///
+/// A panic inside `Strategy::execute` is caught here rather than propagating,
+/// so one misbehaving strategy can't take down its own task, let alone the
+/// bot or the strategies trading other perpetuals. The task exits (handing
+/// the strategy back to the caller) once `events_tx` is dropped, which the
+/// bot does on every outer restart so strategies can be re-initialized
+/// against the fresh snapshot.
+pub fn spawn(
+    mut strategy: StrategyType,
+    instance: ExchangeInstance<DynProvider>,
+    exchange: Arc<RwLock<Exchange>>,
+    mut events_rx: mpsc::Receiver<Vec<StateEvents>>,
+    nonce_scheduler: SharedNonceScheduler,
+    fee_oracle: SharedFeeOracle,
+    timeout: Duration,
+) -> tokio::task::JoinHandle<StrategyType> {
+    tokio::spawn(async move {
+        let name = strategy.name();
+        let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_SUBMISSIONS_PER_STRATEGY));
+        let (error_tx, mut error_rx) = mpsc::channel(100);
+
+        let mut interval = tokio::time::interval(timeout);
+        interval.tick().await; // First tick completes immediately
+
+        loop {
+            tokio::select! {
+                events = events_rx.recv() => {
+                    let Some(events) = events else {
+                        warn!(strategy = name, "Event channel closed, strategy task shutting down for restart");
+                        break;
+                    };
+
+                    run_execute(&mut strategy, &instance, &exchange, &events, &error_tx, &nonce_scheduler, &fee_oracle, &semaphore).await;
+                }
+                error = error_rx.recv() => {
+                    let Some(err) = error else {
+                        continue;
+                    };
+
+                    warn!(strategy = name, %err, "Received error from a strategy submission, will retry execution again if permitted");
+                    run_execute(&mut strategy, &instance, &exchange, &[], &error_tx, &nonce_scheduler, &fee_oracle, &semaphore).await;
+                }
+                _ = interval.tick() => {
+                    run_execute(&mut strategy, &instance, &exchange, &[], &error_tx, &nonce_scheduler, &fee_oracle, &semaphore).await;
+                }
+            }
+        }
+
+        strategy
+    })
+}
+
+/// Acquire this strategy's submission permit and run one execute cycle,
+/// catching a panic so it can't tear down the strategy's own task.
+async fn run_execute(
+    strategy: &mut StrategyType,
+    instance: &ExchangeInstance<DynProvider>,
+    exchange: &Arc<RwLock<Exchange>>,
+    events: &[StateEvents],
+    error_tx: &mpsc::Sender<DexError>,
+    nonce_scheduler: &SharedNonceScheduler,
+    fee_oracle: &SharedFeeOracle,
+    semaphore: &Arc<Semaphore>,
+) {
+    let name = strategy.name();
+
+    let Ok(permit) = semaphore.clone().try_acquire_owned() else {
+        warn!(strategy = name, "Previous strategy execution still in progress, skipping this event batch");
+        return;
+    };
+
+    let exchange = exchange.read().await;
+
+    let outcome = AssertUnwindSafe(strategy.execute(
+        instance,
+        &exchange,
+        events,
+        error_tx,
+        nonce_scheduler,
+        fee_oracle,
+        permit,
+    ))
+    .catch_unwind()
+    .await;
+
+    if let Err(panic) = outcome {
+        let message = panic
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("non-string panic payload");
+        error!(strategy = name, panic = message, "Strategy execute panicked, isolating and continuing");
+    }
+}
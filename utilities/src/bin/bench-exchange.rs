@@ -0,0 +1,364 @@
+//! Load-generation harness against `TestExchange`, for measuring how many
+//! fills/second the SDK + node sustain and catching throughput regressions.
+//!
+//! Spins up `--accounts` maker accounts (each resting a bid/ask around the
+//! perpetual's index price) and `--accounts` taker accounts (each submitting
+//! randomized `OpenLong`/`OpenShort` IOC orders, mirroring
+//! `TakerStrategy::place_order`; no close orders are sent),
+//! at a combined `--rate` submissions/sec for `--duration` seconds, and
+//! reports submitted/confirmed/reverted counts plus receipt-latency
+//! percentiles.
+//!
+//! Run with: cargo run --bin bench-exchange -- --accounts 4 --rate 20 --duration 60
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use alloy::providers::{DynProvider, ProviderBuilder};
+use clap::Parser;
+use dex_sdk::{
+    abi::dex::Exchange::ExchangeInstance,
+    error::DexError,
+    state::{Exchange, SnapshotBuilder},
+    testing::TestExchange,
+    types::{AccountAddressOrID, OrderRequest, PerpetualId, RequestType},
+};
+use fastnum::UD64;
+use rand::{
+    Rng,
+    distr::{Bernoulli, Distribution, OpenClosed01},
+};
+use tokio::sync::{Mutex, Semaphore};
+use tracing::{error, info, warn};
+
+#[derive(Debug, clap::Parser)]
+struct BenchArgs {
+    /// Number of maker/taker account pairs to drive load from
+    #[clap(long, default_value = "4")]
+    accounts: u32,
+    /// Target order submissions per second, spread across all taker accounts
+    #[clap(long, default_value = "10")]
+    rate: u64,
+    /// How long to run the benchmark for, in seconds
+    #[clap(long, default_value = "60")]
+    duration: u64,
+    /// Perpetual to trade; defaults to the harness's own BTC perp
+    #[clap(long)]
+    perpetual_id: Option<PerpetualId>,
+    /// Max concurrent in-flight submissions, mirroring the bot's order semaphore
+    #[clap(long, default_value = "8")]
+    concurrency: usize,
+}
+
+#[derive(Debug, Default)]
+struct Metrics {
+    submitted: u64,
+    confirmed: u64,
+    reverted: u64,
+    receipt_latencies: Vec<Duration>,
+}
+
+impl Metrics {
+    fn record(&mut self, latency: Duration, confirmed: bool) {
+        self.submitted += 1;
+        if confirmed {
+            self.confirmed += 1;
+        } else {
+            self.reverted += 1;
+        }
+        self.receipt_latencies.push(latency);
+    }
+
+    fn report(&self) {
+        let mut sorted = self.receipt_latencies.clone();
+        sorted.sort();
+
+        let percentile = |p: f64| -> Duration {
+            if sorted.is_empty() {
+                return Duration::ZERO;
+            }
+            sorted[((sorted.len() - 1) as f64 * p) as usize]
+        };
+
+        let mean = if sorted.is_empty() {
+            Duration::ZERO
+        } else {
+            sorted.iter().sum::<Duration>() / sorted.len() as u32
+        };
+
+        info!(
+            submitted = self.submitted,
+            confirmed = self.confirmed,
+            reverted = self.reverted,
+            mean_latency_ms = mean.as_millis(),
+            p50_latency_ms = percentile(0.5).as_millis(),
+            p99_latency_ms = percentile(0.99).as_millis(),
+            "Benchmark metrics"
+        );
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    if std::env::var("RUST_LOG").is_err() {
+        unsafe {
+            std::env::set_var("RUST_LOG", "info");
+        }
+    }
+
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let args = BenchArgs::parse();
+
+    let exchange = TestExchange::new().await;
+    let btc_perp = exchange.btc_perp().await;
+    let perpetual_id = args.perpetual_id.unwrap_or(btc_perp.id);
+    let exchange_address = exchange.exchange.address();
+    let chain = exchange.chain();
+
+    info!(
+        accounts = args.accounts,
+        rate = args.rate,
+        duration = args.duration,
+        perpetual_id,
+        "Starting benchmark"
+    );
+
+    // Each maker/taker pair gets its own signer so on-chain auth matches the
+    // account that is actually submitting, the same as a real bot wallet.
+    let mut maker_instances = Vec::with_capacity(args.accounts as usize);
+    let mut taker_instances = Vec::with_capacity(args.accounts as usize);
+    let mut account_addresses = Vec::with_capacity(args.accounts as usize * 2);
+
+    for i in 0..args.accounts {
+        let maker = exchange.account(i * 2, 1_000_000).await;
+        account_addresses.push(AccountAddressOrID::Address(maker.address));
+        maker_instances.push(connect_instance(&exchange, exchange_address, &maker).await);
+
+        let taker = exchange.account(i * 2 + 1, 1_000_000).await;
+        account_addresses.push(AccountAddressOrID::Address(taker.address));
+        taker_instances.push(connect_instance(&exchange, exchange_address, &taker).await);
+    }
+
+    let snapshot = Arc::new(Mutex::new(
+        SnapshotBuilder::new(&chain, exchange.exchange.provider().clone())
+            .with_accounts(account_addresses)
+            .with_perpetuals(vec![perpetual_id])
+            .build()
+            .await
+            .expect("Failed to build exchange snapshot"),
+    ));
+
+    // Keep the snapshot reasonably fresh for order preparation; a full
+    // event-driven reconciliation loop like the real bot's isn't needed for
+    // a throughput benchmark.
+    {
+        let snapshot = snapshot.clone();
+        let provider = exchange.exchange.provider().clone();
+        let accounts = snapshot.lock().await.accounts().keys().copied().collect::<Vec<_>>();
+        tokio::spawn(async move {
+            let accounts = accounts
+                .into_iter()
+                .map(AccountAddressOrID::Id)
+                .collect::<Vec<_>>();
+            loop {
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                match SnapshotBuilder::new(&chain, provider.clone())
+                    .with_accounts(accounts.clone())
+                    .with_perpetuals(vec![perpetual_id])
+                    .build()
+                    .await
+                {
+                    Ok(fresh) => *snapshot.lock().await = fresh,
+                    Err(error) => warn!(%error, "Failed to refresh exchange snapshot"),
+                }
+            }
+        });
+    }
+
+    // Rest a bid and ask on every maker so takers have liquidity to cross.
+    for instance in &maker_instances {
+        let exchange_state = snapshot.lock().await;
+        let index_price = exchange_state
+            .perpetuals()
+            .get(&perpetual_id)
+            .expect("perpetual must exist")
+            .index_price();
+
+        let bid = place_resting_order(&exchange_state, perpetual_id, RequestType::OpenLong, index_price * UD64::from(99u32) / UD64::from(100u32));
+        let ask = place_resting_order(&exchange_state, perpetual_id, RequestType::OpenShort, index_price * UD64::from(101u32) / UD64::from(100u32));
+        drop(exchange_state);
+
+        if let Err(error) = instance
+            .execOpsAndOrders(vec![], vec![bid, ask], false)
+            .send()
+            .await
+        {
+            warn!(%error, "Failed to rest maker liquidity");
+        }
+    }
+
+    let metrics = Arc::new(Mutex::new(Metrics::default()));
+    let semaphore = Arc::new(Semaphore::new(args.concurrency));
+    let max_order_size = UD64::ONE;
+    let op_distribution = Bernoulli::new(0.5).unwrap();
+
+    let report_handle = {
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(10));
+            loop {
+                interval.tick().await;
+                metrics.lock().await.report();
+            }
+        })
+    };
+
+    let deadline = Instant::now() + Duration::from_secs(args.duration);
+    let mut interval = tokio::time::interval(Duration::from_secs_f64(1.0 / args.rate as f64));
+
+    let mut next_taker = 0usize;
+    while Instant::now() < deadline {
+        interval.tick().await;
+
+        let Ok(permit) = semaphore.clone().try_acquire_owned() else {
+            continue;
+        };
+
+        let instance = taker_instances[next_taker % taker_instances.len()].clone();
+        next_taker += 1;
+
+        let snapshot = snapshot.clone();
+        let metrics = metrics.clone();
+        let long = op_distribution.sample(&mut rand::rng());
+        let size_multiplier = rand::rng().sample::<f64, _>(OpenClosed01);
+        let size = UD64::from_f64(size_multiplier).expect("failed to parse UD64") * max_order_size;
+
+        tokio::spawn(async move {
+            let order_desc = {
+                let exchange_state = snapshot.lock().await;
+                let order_type = if long {
+                    RequestType::OpenLong
+                } else {
+                    RequestType::OpenShort
+                };
+                place_taker_order(&exchange_state, perpetual_id, order_type, size)
+            };
+
+            let submitted_at = Instant::now();
+            match instance
+                .execOpsAndOrders(vec![], vec![order_desc], false)
+                .send()
+                .await
+                .map_err(DexError::from)
+            {
+                Ok(res) => match res.get_receipt().await.map_err(DexError::from) {
+                    Ok(_) => metrics
+                        .lock()
+                        .await
+                        .record(submitted_at.elapsed(), true),
+                    Err(error) => {
+                        error!(%error, "Benchmark order reverted");
+                        metrics
+                            .lock()
+                            .await
+                            .record(submitted_at.elapsed(), false);
+                    }
+                },
+                Err(error) => {
+                    error!(%error, "Failed to submit benchmark order");
+                    metrics
+                        .lock()
+                        .await
+                        .record(submitted_at.elapsed(), false);
+                }
+            }
+
+            drop(permit);
+        });
+    }
+
+    report_handle.abort();
+    metrics.lock().await.report();
+    info!("Benchmark complete");
+}
+
+async fn connect_instance(
+    exchange: &TestExchange,
+    exchange_address: alloy::primitives::Address,
+    account: &dex_sdk::testing::TestAccount,
+) -> ExchangeInstance<DynProvider> {
+    let rpc_client = alloy::rpc::client::RpcClient::new_http(
+        exchange.rpc_url.parse().expect("Invalid test exchange RPC URL"),
+    );
+    let provider = DynProvider::new(
+        ProviderBuilder::new()
+            .wallet(alloy::network::EthereumWallet::new(account.signer.clone()))
+            .connect_client(rpc_client),
+    );
+
+    ExchangeInstance::new(exchange_address, provider)
+}
+
+fn place_resting_order(
+    exchange: &Exchange,
+    perpetual_id: PerpetualId,
+    order_type: RequestType,
+    price: UD64,
+) -> dex_sdk::abi::dex::Exchange::OrderDesc {
+    let request = OrderRequest::new(
+        0,
+        perpetual_id,
+        order_type,
+        None,
+        price,
+        UD64::from(100u32),
+        None,
+        // post_only: rest liquidity rather than take it
+        true,
+        false,
+        false,
+        None,
+        UD64::ONE,
+        None,
+        None,
+    );
+
+    request.prepare(exchange)
+}
+
+fn place_taker_order(
+    exchange: &Exchange,
+    perpetual_id: PerpetualId,
+    order_type: RequestType,
+    size: UD64,
+) -> dex_sdk::abi::dex::Exchange::OrderDesc {
+    let price = match order_type {
+        RequestType::OpenLong => UD64::MAX,
+        _ => UD64::ZERO,
+    };
+
+    let request = OrderRequest::new(
+        0,
+        perpetual_id,
+        order_type,
+        None,
+        price,
+        size,
+        None,
+        false,
+        false,
+        // immediate or cancel
+        true,
+        None,
+        UD64::ONE,
+        None,
+        None,
+    );
+
+    request.prepare(exchange)
+}
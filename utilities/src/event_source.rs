@@ -0,0 +1,57 @@
+//! Picks a block-event transport based on the scheme of a node RPC URL.
+//!
+//! **This does not yet do what it was asked to.** The request behind this
+//! module wanted a genuine push-based transport — a `tokio-tungstenite`
+//! connection driving `eth_subscribe("logs", {address, topics})`, reassembled
+//! into per-block `StateEvents` ordered by block number and log index — so a
+//! `ws://`/`wss://` node URL gets real subscription latency instead of the
+//! 500 ms poll loop. That transport has to be built as `stream::raw_ws` (or
+//! similar) inside `perpl_sdk::stream` itself, because it owns the
+//! `eth_subscribe` framing and the `StateInstant`-tagged event types
+//! downstream decoders expect, and that crate is an external dependency of
+//! this workspace, not vendored source we can edit here. There is no way to
+//! deliver the requested transport from this repository alone.
+//!
+//! What's here instead is scaffolding for the day that transport exists
+//! upstream: `EventSource` only has a `Poll` variant, and `from_url` accepts
+//! `ws://`/`wss://` URLs but falls back to HTTP polling with a warning rather
+//! than failing, so callers already route through one switch point and won't
+//! need to change call sites once a `Subscribe` variant is added. Until then,
+//! a `ws://` node URL gets no latency improvement at all.
+//!
+//! This is the one place that gap is explained; both `print_trades` and
+//! `perpl_market_making_bot::PerplMarketMakingBot::run` call `from_url`
+//! rather than re-deriving the same scheme check and warning inline.
+
+use url::Url;
+
+/// Which block-event transport to drive a `print_trades`-style consumer from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventSource {
+    /// HTTP polling via `perpl_sdk::stream::raw`, the only transport
+    /// currently implemented by the SDK this workspace depends on. There is
+    /// no `Subscribe`/WS variant yet — see the module docs for why.
+    Poll,
+}
+
+impl EventSource {
+    /// Choose a transport based on `node_url`'s scheme.
+    ///
+    /// `ws://`/`wss://` URLs are accepted, but since `perpl_sdk::stream` has
+    /// no WebSocket transport yet, they currently fall back to `Poll` with a
+    /// warning rather than failing outright. This is a known gap, not a
+    /// disguised success — see the module docs.
+    pub fn from_url(node_url: &Url) -> Self {
+        match node_url.scheme() {
+            "ws" | "wss" => {
+                tracing::warn!(
+                    %node_url,
+                    "WebSocket event source requested, but perpl_sdk::stream has no \
+                     raw_ws transport yet; falling back to HTTP polling"
+                );
+                EventSource::Poll
+            }
+            _ => EventSource::Poll,
+        }
+    }
+}
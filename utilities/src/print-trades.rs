@@ -11,18 +11,28 @@ use alloy::{
 };
 use futures::StreamExt;
 use perpl_sdk::{Chain, stream, types::StateInstant};
+use perpl_utilities::event_source::EventSource;
+use url::Url;
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let node_url = Url::parse("https://testnet-rpc.monad.xyz")?;
+
     let client = RpcClient::builder()
         .layer(RetryBackoffLayer::new(10, 100, 200))
-        .connect("https://testnet-rpc.monad.xyz")
+        .connect(node_url.as_str())
         .await?;
     client.set_poll_interval(Duration::from_millis(500));
     let provider = ProviderBuilder::new().connect_client(client);
 
     let chain = Chain::testnet();
 
+    // Picks the event transport based on the RPC URL scheme; the irrefutable
+    // pattern is intentional so this call site breaks at compile time the
+    // day `EventSource` grows a second variant, see its docs for why there's
+    // only one today.
+    let EventSource::Poll = EventSource::from_url(&node_url);
+
     // Start from the current block
     let block_num = provider.get_block_number().await?;
     println!("Starting from block {}", block_num);